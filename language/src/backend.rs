@@ -0,0 +1,884 @@
+//! The proof-assistant-agnostic half of the translator: AST traversal
+//! (`translate_expression`, `translate_statement`, ...) lives here, generic
+//! over a [`Backend`] that supplies the target-specific syntax (types,
+//! operators, let-bindings, loops, ...). [`crate::rustspec_to_fstar`] and
+//! [`crate::rustspec_to_coq`] each provide one such `Backend`.
+//!
+//! Splitting things this way means a new target only has to implement
+//! [`Backend`]; it gets the AST walk (and its `RcDoc` plumbing) for free.
+
+use crate::rustspec::*;
+
+use crate::typechecker::{DictEntry, TypeDict};
+use core::iter::IntoIterator;
+use heck::SnakeCase;
+use pretty::RcDoc;
+use regex::Regex;
+use rustc_ast::ast::BinOpKind;
+use rustc_session::Session;
+
+/// The class of operand a binary operator is applied to, independent of
+/// the target language: the same `BinOpKind` prints as a different symbol
+/// for a secret machine integer, a public `usize`/`isize`, a `Seq`/array,
+/// or a natural-integer modulus. [`classify_typ`] computes this from a
+/// `Typ`; a [`Backend`] then only has to supply a table from
+/// `(BinOpKind, OperandClass)` to its symbol, instead of re-deriving the
+/// class inline in a big match (as `binop` used to).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandClass {
+    /// Machine integers (secret or public) not covered by a more specific
+    /// class below; also the fallback `PublicSize` and `Seq` fall back to
+    /// for operators they don't special-case (`NatMod` has no fallback).
+    SecretInt,
+    /// `usize`/`isize`.
+    PublicSize,
+    /// `Seq<_>`/`Array<_, _>` (and aliases thereof).
+    Seq,
+    /// A natural-integer modulus (`nat_mod`).
+    NatMod,
+}
+
+/// Classify `op_typ` into an [`OperandClass`], unwrapping `Array`/`Alias`
+/// type-dictionary entries the same way `binop` used to do inline.
+pub fn classify_typ(op_typ: &Typ, typ_dict: &TypeDict) -> OperandClass {
+    if let BaseTyp::Named(ident, _) = &(op_typ.1).0 {
+        let ident = match &ident.0 {
+            Ident::Original(i) => i,
+            Ident::Hacspec(_, _) => panic!(), // should not happen
+        };
+        if let Some((inner_ty, entry)) = typ_dict.get(ident) {
+            return match entry {
+                DictEntry::NaturalInteger => OperandClass::NatMod,
+                DictEntry::Array | DictEntry::Alias => classify_typ(inner_ty, typ_dict),
+            };
+        }
+    }
+    match &(op_typ.1).0 {
+        BaseTyp::Usize | BaseTyp::Isize => OperandClass::PublicSize,
+        BaseTyp::Seq(_) | BaseTyp::Array(_, _) => OperandClass::Seq,
+        _ => OperandClass::SecretInt,
+    }
+}
+
+/// A backend's operator table: one row per `(operator, operand class)`
+/// combination it special-cases, mapping to the symbol it prints.
+pub type BinopTable = &'static [(BinOpKind, OperandClass, &'static str)];
+
+fn lookup_binop<'a>(table: BinopTable, op: &BinOpKind, class: OperandClass) -> Option<RcDoc<'a, ()>> {
+    table
+        .iter()
+        .find(|(o, c, _)| o == op && *c == class)
+        .map(|(_, _, sym)| RcDoc::as_string(*sym))
+}
+
+/// Target-specific syntax for one proof-assistant backend. Everything
+/// that depends only on AST shape (not on the target language) lives in
+/// the free `translate_*` functions below instead, which take `&B` and
+/// dispatch to it for the parts that vary.
+pub trait Backend {
+    /// Translate a base type (`lseq`/`nat_mod` for F*, the Coq
+    /// equivalents for Coq, ...).
+    fn base_typ<'a>(&self, tau: BaseTyp) -> RcDoc<'a, ()>;
+    /// Translate a literal value.
+    fn literal<'a>(&self, lit: &Literal) -> RcDoc<'a, ()>;
+    /// This backend's [`BinopTable`]: which symbol each `(BinOpKind,
+    /// OperandClass)` pair prints as.
+    fn binop_table(&self) -> BinopTable;
+    /// Translate a binary operator, given the type it operates over (the
+    /// same operator prints differently over machine integers, `Seq`s and
+    /// natural-integer moduli). Provided in terms of [`classify_typ`] and
+    /// [`Backend::binop_table`]; a backend only needs to override this if
+    /// its operator choice depends on more than the operand's class.
+    fn binop<'a, 'b>(&self, op: &'a BinOpKind, op_typ: &'b Typ, typ_dict: &TypeDict) -> RcDoc<'a, ()> {
+        let class = classify_typ(op_typ, typ_dict);
+        if let Some(doc) = lookup_binop(self.binop_table(), op, class) {
+            return doc;
+        }
+        // Every class but `NatMod` falls back to the generic `SecretInt`
+        // entry for operators it doesn't special-case; `NatMod` only
+        // supports the five modular-arithmetic operators it lists.
+        if class != OperandClass::NatMod {
+            if let Some(doc) = lookup_binop(self.binop_table(), op, OperandClass::SecretInt) {
+                return doc;
+            }
+        }
+        unimplemented!("no operator table entry for {:?} over {:?}", op, class)
+    }
+    /// Translate a unary operator.
+    fn unop<'a, 'b>(&self, op: &'a UnOpKind, op_typ: &'b Typ) -> RcDoc<'a, ()>;
+    /// Translate a (possibly type-qualified, e.g. `Seq::from_slice`)
+    /// function name into the backend's module/function naming scheme.
+    fn func_name<'a>(
+        &self,
+        prefix: Option<Spanned<BaseTyp>>,
+        name: &'a Ident,
+        typ_dict: &'a TypeDict,
+    ) -> RcDoc<'a, ()>;
+    /// `let pat [: typ] = expr [in]`, with `in` omitted at toplevel.
+    /// `cfg` controls how the body is indented relative to `pat`.
+    fn let_binding<'a>(
+        &self,
+        pat: RcDoc<'a, ()>,
+        typ: Option<RcDoc<'a, ()>>,
+        expr: RcDoc<'a, ()>,
+        toplevel: bool,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()>;
+    /// A fold-style for loop over `[e1, e2)`, binding the loop variable
+    /// `x` and the running accumulator `acc` in `body`.
+    fn for_loop<'a>(
+        &self,
+        x: RcDoc<'a, ()>,
+        e1: RcDoc<'a, ()>,
+        e2: RcDoc<'a, ()>,
+        acc: RcDoc<'a, ()>,
+        body: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()>;
+    /// `if cond then branch1 else branch2`.
+    fn conditional<'a>(
+        &self,
+        cond: RcDoc<'a, ()>,
+        b1: RcDoc<'a, ()>,
+        b2: Option<RcDoc<'a, ()>>,
+    ) -> RcDoc<'a, ()>;
+    /// Functional array/sequence index: `x[e]`.
+    fn array_index<'a>(&self, x: RcDoc<'a, ()>, e: RcDoc<'a, ()>) -> RcDoc<'a, ()>;
+    /// Functional array/sequence update: the expression for "`x` with
+    /// index `e1` set to `e2`" (assigned back to `x` by the caller).
+    fn array_update<'a>(
+        &self,
+        x: RcDoc<'a, ()>,
+        e1: RcDoc<'a, ()>,
+        e2: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()>;
+    /// Build an array/sequence literal from a list of element expressions.
+    fn new_array<'a>(&self, elements: Vec<RcDoc<'a, ()>>) -> RcDoc<'a, ()>;
+    /// `type name = <fixed-length vector of cell_t, length size>`, for
+    /// `Item::ArrayDecl`. `cfg` controls how the type is indented (see
+    /// [`FormatConfig::align_chains`]).
+    fn array_decl<'a>(
+        &self,
+        name: RcDoc<'a, ()>,
+        size: RcDoc<'a, ()>,
+        cell_t: RcDoc<'a, ()>,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()>;
+    /// The canvas + modular-integer type pair for `Item::NaturalIntegerDecl`,
+    /// plus the full `nat_mod` interface specialized to `modulo`/
+    /// `canvas_size`: the `from`/`to_byte_seq_le/be` conversions,
+    /// `from_literal`, and the modular `add`/`sub`/`mul`/`pow_mod`
+    /// wrappers. `secrecy` picks the public or secret variant of the
+    /// operations that would otherwise branch on the integer's value
+    /// (`pow_mod`, equality) -- a secret `nat_mod` only gets the
+    /// constant-time ones. `cfg` controls how each declaration is
+    /// indented.
+    fn nat_mod_decl<'a>(
+        &self,
+        nat_name: RcDoc<'a, ()>,
+        canvas_name: RcDoc<'a, ()>,
+        canvas_size: RcDoc<'a, ()>,
+        modulo: &'a str,
+        secrecy: &'a Secrecy,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()>;
+    /// The module preamble written before the translated items (module
+    /// declaration, imports, ...). Plain text rather than `RcDoc` since
+    /// it's a fixed header, not part of the pretty-printed AST. `cfg`
+    /// supplies the F* backend's `--fuel`/`--ifuel`/`--z3rlimit`.
+    fn module_header(&self, module_name: &str, cfg: &FormatConfig) -> String;
+    /// Translate an `expr as target` integer cast, given the `BaseTyp`s of
+    /// the operand and the cast target.
+    fn integer_cast<'a>(
+        &self,
+        source: &BaseTyp,
+        target: &BaseTyp,
+        expr: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()>;
+    /// Wrap a fallible function's successful tail value: `Ok expr` /
+    /// `Some expr` (or the target's equivalents).
+    fn wrap_success<'a>(&self, kind: FallibleKind, expr: RcDoc<'a, ()>) -> RcDoc<'a, ()>;
+    /// The monadic bind for `let pat = scrutinee?; continuation`: match
+    /// `scrutinee`, short-circuiting on `Err`/`None` and otherwise binding
+    /// `pat` before running `continuation`.
+    fn monadic_bind<'a>(
+        &self,
+        kind: FallibleKind,
+        pat: RcDoc<'a, ()>,
+        scrutinee: RcDoc<'a, ()>,
+        continuation: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()>;
+    /// `Item::TraitDecl`'s typeclass: `name`, one type parameter per
+    /// associated type, and each method's name paired with its (body-less)
+    /// function type, e.g. `t1 -> t2 -> ret`.
+    fn trait_decl<'a>(
+        &self,
+        name: RcDoc<'a, ()>,
+        type_params: Vec<RcDoc<'a, ()>>,
+        methods: Vec<(RcDoc<'a, ()>, RcDoc<'a, ()>)>,
+    ) -> RcDoc<'a, ()>;
+    /// `Item::ImplDecl`'s typeclass instance: `trait_name` instantiated at
+    /// `self_typ` (with `type_args` filling in the trait's associated
+    /// types), binding each method name to its translated body.
+    fn impl_decl<'a>(
+        &self,
+        trait_name: RcDoc<'a, ()>,
+        self_typ: RcDoc<'a, ()>,
+        type_args: Vec<RcDoc<'a, ()>>,
+        methods: Vec<(RcDoc<'a, ()>, RcDoc<'a, ()>)>,
+    ) -> RcDoc<'a, ()>;
+}
+
+/// Layout knobs for the emitted proof, threaded through
+/// [`translate_program`]/[`translate_item`] instead of hard-coded: the
+/// render width, the F* `#set-options` pragma's `fuel`/`ifuel`/`z3rlimit`
+/// (other backends ignore these), and the indentation unit `array_decl`/
+/// `nat_mod_decl`/`let_binding` (toplevel or not) nest their bodies by.
+#[derive(Debug, Clone, Copy)]
+pub struct FormatConfig {
+    /// Column width `translate_and_write_to_file` wraps lines at.
+    pub width: usize,
+    /// F*'s `--fuel`.
+    pub fuel: u8,
+    /// F*'s `--ifuel`.
+    pub ifuel: u8,
+    /// F*'s `--z3rlimit`.
+    pub z3rlimit: u32,
+    /// Spaces a nested continuation line indents by.
+    pub indent: isize,
+    /// When set, align a declaration's continuation lines (the
+    /// `lseq`/`nat_mod` type and every `let`/`def` body) to the
+    /// column of the first line instead of nesting by a flat `indent` --
+    /// the same choice editor "align chained calls" settings offer.
+    pub align_chains: bool,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            width: 80,
+            fuel: 0,
+            ifuel: 1,
+            z3rlimit: 15,
+            indent: 2,
+            align_chains: false,
+        }
+    }
+}
+
+/// Nest `doc` by `cfg`'s indentation unit, or (`align_chains`) align it to
+/// the column the nest would otherwise start from instead of a flat
+/// offset.
+pub(crate) fn nest_or_align<'a>(cfg: &FormatConfig, doc: RcDoc<'a, ()>) -> RcDoc<'a, ()> {
+    if cfg.align_chains {
+        RcDoc::column(move |col| doc.clone().nest(col as isize))
+    } else {
+        doc.nest(cfg.indent)
+    }
+}
+
+/// Which monad a fallible function's `Result`/`Option` return type
+/// desugars `?` into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallibleKind {
+    Result,
+    Option,
+}
+
+/// Recognize `ret` as `Result<_, _>` or `Option<_>` by name -- the
+/// hacspec subset doesn't track arbitrary generic type constructors, so
+/// (like `FStarBackend`'s secret-integer detection) this matches on the
+/// translated identifier rather than a dedicated `BaseTyp` variant.
+pub fn fallible_kind(ret: &BaseTyp) -> Option<FallibleKind> {
+    if let BaseTyp::Named(ident, _) = ret {
+        if let Ident::Original(name) = &ident.0 {
+            return match name.as_str() {
+                "Result" => Some(FallibleKind::Result),
+                "Option" => Some(FallibleKind::Option),
+                _ => None,
+            };
+        }
+    }
+    None
+}
+
+pub(crate) fn make_let_binding<'a>(
+    keyword: &'static str,
+    pat: RcDoc<'a, ()>,
+    typ: Option<RcDoc<'a, ()>>,
+    assign_op: &'static str,
+    expr: RcDoc<'a, ()>,
+    toplevel: bool,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    RcDoc::as_string(keyword)
+        .append(RcDoc::space())
+        .append(
+            pat.append(match typ {
+                None => RcDoc::nil(),
+                Some(tau) => RcDoc::space()
+                    .append(RcDoc::as_string(":"))
+                    .append(RcDoc::space())
+                    .append(tau),
+            })
+            .group(),
+        )
+        .append(RcDoc::space())
+        .append(RcDoc::as_string(assign_op))
+        .group()
+        .append(nest_or_align(cfg, RcDoc::line().append(expr.group())))
+        .append(if toplevel {
+            RcDoc::nil()
+        } else {
+            RcDoc::line().append(RcDoc::as_string("in"))
+        })
+}
+
+pub(crate) fn make_tuple<'a, I: IntoIterator<Item = RcDoc<'a, ()>>>(args: I) -> RcDoc<'a, ()> {
+    RcDoc::as_string("(")
+        .append(
+            RcDoc::line_()
+                .append(RcDoc::intersperse(
+                    args.into_iter(),
+                    RcDoc::as_string(",").append(RcDoc::line()),
+                ))
+                .group()
+                .nest(2),
+        )
+        .append(RcDoc::line_())
+        .append(RcDoc::as_string(")"))
+        .group()
+}
+
+pub(crate) fn make_list<'a, I: IntoIterator<Item = RcDoc<'a, ()>>>(args: I) -> RcDoc<'a, ()> {
+    RcDoc::as_string("[")
+        .append(
+            RcDoc::line_()
+                .append(RcDoc::intersperse(
+                    args.into_iter(),
+                    RcDoc::as_string(";").append(RcDoc::line()),
+                ))
+                .group()
+                .nest(2),
+        )
+        .append(RcDoc::line_())
+        .append(RcDoc::as_string("]"))
+        .group()
+}
+
+pub(crate) fn make_typ_tuple<'a, I: IntoIterator<Item = RcDoc<'a, ()>>>(
+    sep: &'static str,
+    args: I,
+) -> RcDoc<'a, ()> {
+    RcDoc::as_string("(")
+        .append(
+            RcDoc::line_()
+                .append(RcDoc::intersperse(
+                    args.into_iter(),
+                    RcDoc::space()
+                        .append(RcDoc::as_string(sep))
+                        .append(RcDoc::line()),
+                ))
+                .group()
+                .nest(2),
+        )
+        .append(RcDoc::line_())
+        .append(RcDoc::as_string(")"))
+        .group()
+}
+
+pub(crate) fn make_paren<'a>(e: RcDoc<'a, ()>) -> RcDoc<'a, ()> {
+    RcDoc::as_string("(")
+        .append(RcDoc::line_().append(e).group().nest(2))
+        .append(RcDoc::as_string(")"))
+        .group()
+}
+
+pub(crate) fn translate_ident<'a>(x: Ident) -> RcDoc<'a, ()> {
+    let ident_str = match x {
+        Ident::Original(s) => s.clone(),
+        Ident::Hacspec(id, s) => format!("{}_{}", s, id.0),
+    };
+    translate_ident_str(ident_str)
+}
+
+pub(crate) fn translate_ident_str<'a>(ident_str: String) -> RcDoc<'a, ()> {
+    let mut ident_str = ident_str.clone();
+    let secret_int_regex = Regex::new(r"(?P<prefix>(U|I))(?P<digits>\d{1,3})").unwrap();
+    ident_str = secret_int_regex
+        .replace_all(&ident_str, r"${prefix}int${digits}")
+        .to_string();
+    let secret_signed_int_fix = Regex::new(r"iint").unwrap();
+    ident_str = secret_signed_int_fix
+        .replace_all(&ident_str, "int")
+        .to_string();
+    let mut snake_case_ident = ident_str.to_snake_case();
+    if snake_case_ident == "new" {
+        snake_case_ident = "new_".to_string();
+    }
+    RcDoc::as_string(snake_case_ident)
+}
+
+fn translate_typ<B: Backend>(b: &B, (_, (tau, _)): &Typ) -> RcDoc<()> {
+    b.base_typ(tau.clone())
+}
+
+/// A trait method's signature as a bare function type (`t1 -> t2 -> ret`),
+/// for `Item::TraitDecl`'s `class` fields, which carry no argument names or
+/// bodies.
+fn translate_func_type<'a, B: Backend>(b: &B, sig: &'a FuncSig) -> RcDoc<'a, ()> {
+    RcDoc::intersperse(
+        sig.args
+            .iter()
+            .map(|(_, (tau, _))| b.base_typ(tau.clone()))
+            .chain(std::iter::once(b.base_typ(sig.ret.0.clone()))),
+        RcDoc::space().append(RcDoc::as_string("->")).append(RcDoc::space()),
+    )
+}
+
+/// An impl method's body as a lambda (`fun (x1 : t1) (x2 : t2) -> body`),
+/// for `Item::ImplDecl`'s `instance` fields, which bind each trait method
+/// to a concrete implementation.
+fn translate_func_lambda<'a, B: Backend>(
+    b: &B,
+    sig: &'a FuncSig,
+    blk: &'a Block,
+    typ_dict: &'a TypeDict,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    RcDoc::as_string("fun")
+        .append(RcDoc::space())
+        .append(if sig.args.len() > 0 {
+            RcDoc::intersperse(
+                sig.args.iter().map(|((x, _), (tau, _))| {
+                    make_paren(
+                        translate_ident(x.clone())
+                            .append(RcDoc::space())
+                            .append(RcDoc::as_string(":"))
+                            .append(RcDoc::space())
+                            .append(b.base_typ(tau.clone())),
+                    )
+                }),
+                RcDoc::space(),
+            )
+        } else {
+            RcDoc::as_string("()")
+        })
+        .append(RcDoc::space())
+        .append(RcDoc::as_string("->"))
+        .append(nest_or_align(
+            cfg,
+            RcDoc::line().append(
+                translate_block(b, blk, false, typ_dict, fallible_kind(&sig.ret.0), cfg)
+                    .append(if let BaseTyp::Unit = sig.ret.0 {
+                        RcDoc::hardline().append(RcDoc::as_string("()"))
+                    } else {
+                        RcDoc::nil()
+                    })
+                    .group(),
+            ),
+        ))
+        .group()
+}
+
+fn translate_pattern<'a>(p: &'a Pattern) -> RcDoc<'a, ()> {
+    match p {
+        Pattern::IdentPat(x) => translate_ident(x.clone()),
+        Pattern::WildCard => RcDoc::as_string("_"),
+        Pattern::Tuple(pats) => make_tuple(pats.iter().map(|(pat, _)| translate_pattern(pat))),
+    }
+}
+
+pub fn translate_expression<'a, B: Backend>(
+    b: &B,
+    e: &'a Expression,
+    typ_dict: &'a TypeDict,
+) -> RcDoc<'a, ()> {
+    match e {
+        Expression::Binary((op, _), ref e1, ref e2, op_typ) => {
+            let e1 = &e1.0;
+            let e2 = &e2.0;
+            make_paren(translate_expression(b, e1, typ_dict))
+                .append(RcDoc::space())
+                .append(b.binop(op, op_typ.as_ref().unwrap(), typ_dict))
+                .append(RcDoc::space())
+                .append(make_paren(translate_expression(b, e2, typ_dict)))
+                .group()
+        }
+        Expression::Unary(op, e1, op_typ) => {
+            let e1 = &e1.0;
+            b.unop(op, op_typ.as_ref().unwrap())
+                .append(RcDoc::space())
+                .append(make_paren(translate_expression(b, e1, typ_dict)))
+                .group()
+        }
+        Expression::Lit(lit) => b.literal(lit),
+        Expression::Tuple(es) => {
+            make_tuple(es.into_iter().map(|(e, _)| translate_expression(b, &e, typ_dict)))
+        }
+        Expression::Named(p) => translate_ident(p.clone()),
+        Expression::FuncCall(prefix, name, args) => {
+            b.func_name(prefix.clone(), &name.0, typ_dict).append(RcDoc::concat(
+                args.iter().map(|((arg, _), _)| {
+                    RcDoc::space().append(make_paren(translate_expression(b, arg, typ_dict)))
+                }),
+            ))
+        }
+        Expression::MethodCall(sel_arg, sel_typ, (f, _), args) => {
+            b.func_name(sel_typ.clone().map(|x| x.1), f, typ_dict)
+                .append(
+                    RcDoc::space()
+                        .append(make_paren(translate_expression(b, &(sel_arg.0).0, typ_dict))),
+                )
+                .append(RcDoc::concat(args.iter().map(|((arg, _), _)| {
+                    RcDoc::space().append(make_paren(translate_expression(b, arg, typ_dict)))
+                })))
+        }
+        Expression::ArrayIndex(x, e2) => {
+            let e2 = &e2.0;
+            b.array_index(
+                make_paren(translate_ident(x.0.clone())),
+                make_paren(translate_expression(b, e2, typ_dict)),
+            )
+        }
+        Expression::NewArray(_, _, args) => b.new_array(
+            args.iter()
+                .map(|(e, _)| translate_expression(b, e, typ_dict))
+                .collect(),
+        ),
+        Expression::IntegerCasting(e1, (source_typ, target_typ)) => {
+            let e1 = &e1.0;
+            b.integer_cast(
+                source_typ,
+                target_typ,
+                make_paren(translate_expression(b, e1, typ_dict)),
+            )
+        }
+        // `?` only has a translation as the right-hand side of a `let`
+        // inside a fallible function's body -- see `translate_fallible_stmts`,
+        // which matches it there before recursing into this function.
+        Expression::QuestionMark(_) => {
+            unimplemented!("`?` is only supported directly in a `let` binding")
+        }
+    }
+}
+
+pub fn translate_statement<'a, B: Backend>(
+    b: &B,
+    s: &'a Statement,
+    typ_dict: &'a TypeDict,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    match s {
+        Statement::LetBinding((pat, _), typ, (expr, _)) => b.let_binding(
+            translate_pattern(pat),
+            typ.as_ref().map(|(typ, _)| translate_typ(b, typ)),
+            translate_expression(b, expr, typ_dict),
+            false,
+            cfg,
+        ),
+        Statement::Reassignment((x, _), (e1, _)) => b.let_binding(
+            translate_ident(x.clone()),
+            None,
+            translate_expression(b, e1, typ_dict),
+            false,
+            cfg,
+        ),
+        Statement::ArrayUpdate((x, _), (e1, _), (e2, _)) => b.let_binding(
+            translate_ident(x.clone()),
+            None,
+            b.array_update(
+                translate_ident(x.clone()),
+                make_paren(translate_expression(b, e1, typ_dict)),
+                make_paren(translate_expression(b, e2, typ_dict)),
+            ),
+            false,
+            cfg,
+        ),
+        Statement::ReturnExp(e1) => translate_expression(b, e1, typ_dict),
+        Statement::Conditional((cond, _), (b1, _), b2, mutated) => {
+            let mutated_info = mutated.as_ref().unwrap().as_ref();
+            b.let_binding(
+                make_tuple(mutated_info.vars.iter().map(|i| translate_ident(i.clone()))),
+                None,
+                b.conditional(
+                    translate_expression(b, cond, typ_dict),
+                    translate_block(b, b1, true, typ_dict, None, cfg)
+                        .append(RcDoc::hardline())
+                        .append(translate_statement(b, &mutated_info.stmt, typ_dict, cfg)),
+                    match b2 {
+                        None => Some(translate_statement(b, &mutated_info.stmt, typ_dict, cfg)),
+                        Some((b2, _)) => Some(
+                            translate_block(b, b2, true, typ_dict, None, cfg)
+                                .append(RcDoc::hardline())
+                                .append(translate_statement(b, &mutated_info.stmt, typ_dict, cfg)),
+                        ),
+                    },
+                ),
+                false,
+                cfg,
+            )
+        }
+        Statement::ForLoop((x, _), (e1, _), (e2, _), (blk, _)) => {
+            let mutated_info = blk.mutated.as_ref().unwrap().as_ref();
+            let mut_tuple =
+                make_tuple(mutated_info.vars.iter().map(|i| translate_ident(i.clone())));
+            let body = translate_block(b, blk, true, typ_dict, None, cfg)
+                .append(RcDoc::hardline())
+                .append(translate_statement(b, &mutated_info.stmt, typ_dict, cfg));
+            let loop_expr = b.for_loop(
+                translate_ident(x.clone()),
+                make_paren(translate_expression(b, e1, typ_dict)),
+                make_paren(translate_expression(b, e2, typ_dict)),
+                mut_tuple.clone(),
+                body,
+            );
+            b.let_binding(mut_tuple, None, loop_expr, false, cfg)
+        }
+    }
+    .group()
+}
+
+/// `fallible`: `Some(kind)` for the body of a function whose return type
+/// is a `Result`/`Option` (see [`fallible_kind`]) -- only ever passed for
+/// a function's top-level body; `?` inside a nested `if`/`for` block
+/// (the `translate_statement` call sites below) isn't supported yet, so
+/// those always pass `None`.
+pub fn translate_block<'a, B: Backend>(
+    b: &B,
+    blk: &'a Block,
+    omit_extra_unit: bool,
+    typ_dict: &'a TypeDict,
+    fallible: Option<FallibleKind>,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    if let Some(kind) = fallible {
+        // A fallible block's value is already `Ok`/`Some`-wrapped, or it
+        // short-circuits via `Err`/`None` -- the unit-append below, which
+        // exists only for `Unit`-returning blocks, never applies here.
+        return translate_fallible_stmts(b, &blk.stmts, kind, typ_dict, cfg);
+    }
+    RcDoc::intersperse(
+        blk.stmts
+            .iter()
+            .map(|(i, _)| translate_statement(b, i, typ_dict, cfg).group()),
+        RcDoc::hardline(),
+    )
+    .append(match (&blk.return_typ, omit_extra_unit) {
+        (None, _) => panic!(), // should not happen,
+        (Some(((Borrowing::Consumed, _), (BaseTyp::Unit, _))), false) => {
+            RcDoc::hardline().append(RcDoc::as_string("()"))
+        }
+        (Some(_), _) => RcDoc::nil(),
+    })
+}
+
+/// Desugar a fallible function's statement list: `let x = e?; rest`
+/// becomes a monadic bind whose success arm is the (recursively
+/// desugared) translation of `rest`, and the final tail expression is
+/// wrapped `Ok`/`Some`. Binds are right-associated by construction, since
+/// each one's continuation is built from the statements that follow it.
+fn translate_fallible_stmts<'a, B: Backend>(
+    b: &B,
+    stmts: &'a [Spanned<Statement>],
+    kind: FallibleKind,
+    typ_dict: &'a TypeDict,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    let (stmt, rest) = stmts
+        .split_first()
+        .expect("a fallible function's body should not be empty");
+    match &stmt.0 {
+        Statement::LetBinding((pat, _), _typ, (Expression::QuestionMark(inner), _)) => {
+            let inner = &inner.0;
+            b.monadic_bind(
+                kind,
+                translate_pattern(pat),
+                make_paren(translate_expression(b, inner, typ_dict)),
+                translate_fallible_stmts(b, rest, kind, typ_dict, cfg),
+            )
+        }
+        Statement::ReturnExp(e1) if rest.is_empty() => {
+            b.wrap_success(kind, translate_expression(b, e1, typ_dict))
+        }
+        _ => translate_statement(b, &stmt.0, typ_dict, cfg)
+            .append(RcDoc::hardline())
+            .append(translate_fallible_stmts(b, rest, kind, typ_dict, cfg)),
+    }
+}
+
+/// Note: the `Item::TraitDecl`/`Item::ImplDecl` arms below assume those two
+/// variants exist on `rustspec::Item` (one type parameter per associated
+/// type, an instance's trait name/self type/type arguments/method bodies,
+/// respectively -- see the per-arm comments). `language/src/rustspec.rs`
+/// itself isn't part of this tree, so that AST change has to land
+/// alongside this one for `translate_item` to actually compile; this
+/// commit is the backend-consumption half only, not a standalone change.
+fn translate_item<'a, B: Backend>(
+    b: &B,
+    i: &'a Item,
+    typ_dict: &'a TypeDict,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    match i {
+        Item::FnDecl((f, _), sig, (blk, _)) => b.let_binding(
+            translate_ident(f.clone())
+                .append(RcDoc::line())
+                .append(if sig.args.len() > 0 {
+                    RcDoc::intersperse(
+                        sig.args.iter().map(|((x, _), (tau, _))| {
+                            make_paren(
+                                translate_ident(x.clone())
+                                    .append(RcDoc::space())
+                                    .append(RcDoc::as_string(":"))
+                                    .append(RcDoc::space())
+                                    .append(translate_typ(b, tau)),
+                            )
+                        }),
+                        RcDoc::line(),
+                    )
+                } else {
+                    RcDoc::as_string("()")
+                })
+                .append(RcDoc::line())
+                .append(
+                    RcDoc::as_string(":")
+                        .append(RcDoc::space())
+                        .append(b.base_typ(sig.ret.0.clone()))
+                        .group(),
+                ),
+            None,
+            translate_block(b, blk, false, typ_dict, fallible_kind(&sig.ret.0), cfg)
+                .append(if let BaseTyp::Unit = sig.ret.0 {
+                    RcDoc::hardline().append(RcDoc::as_string("()"))
+                } else {
+                    RcDoc::nil()
+                })
+                .group(),
+            true,
+            cfg,
+        ),
+        Item::ArrayDecl(name, size, cell_t) => b.array_decl(
+            translate_ident(name.0.clone()),
+            make_paren(translate_expression(b, &size.0, typ_dict)),
+            make_paren(b.base_typ(cell_t.0.clone())),
+            cfg,
+        ),
+        Item::ConstDecl(name, ty, e) => b.let_binding(
+            translate_ident(name.0.clone()),
+            Some(b.base_typ(ty.0.clone())),
+            translate_expression(b, &e.0, typ_dict),
+            true,
+            cfg,
+        ),
+        Item::NaturalIntegerDecl(nat_name, canvas_name, secrecy, canvas_size, modulo) => {
+            b.nat_mod_decl(
+                translate_ident(nat_name.0.clone()),
+                translate_ident(canvas_name.0.clone()),
+                make_paren(translate_expression(b, &canvas_size.0, typ_dict)),
+                &modulo.0,
+                secrecy,
+                cfg,
+            )
+        }
+        // `type_params` are the trait's associated types (one F* `class`
+        // type parameter each); `methods` pairs each method's name with its
+        // signature only -- a trait declares no bodies.
+        Item::TraitDecl((name, _), type_params, methods) => b.trait_decl(
+            translate_ident(name.clone()),
+            type_params
+                .iter()
+                .map(|(p, _)| translate_ident(p.clone()))
+                .collect(),
+            methods
+                .iter()
+                .map(|((m, _), sig)| (translate_ident(m.clone()), translate_func_type(b, sig)))
+                .collect(),
+        ),
+        // `type_args` instantiates the trait's associated types for this
+        // impl; `methods` pairs each method's name with its concrete
+        // signature and body, the same shape `Item::FnDecl` carries.
+        Item::ImplDecl((trait_name, _), (self_ty, _), type_args, methods) => b.impl_decl(
+            translate_ident(trait_name.clone()),
+            b.base_typ(self_ty.clone()),
+            type_args.iter().map(|(tau, _)| b.base_typ(tau.clone())).collect(),
+            methods
+                .iter()
+                .map(|((m, _), sig, (blk, _))| {
+                    (
+                        translate_ident(m.clone()),
+                        translate_func_lambda(b, sig, blk, typ_dict, cfg),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn translate_program<'a, B: Backend>(
+    b: &B,
+    p: &'a Program,
+    typ_dict: &'a TypeDict,
+    cfg: &FormatConfig,
+) -> RcDoc<'a, ()> {
+    RcDoc::concat(p.items.iter().map(|(i, _)| {
+        translate_item(b, i, typ_dict, cfg)
+            .append(RcDoc::hardline())
+            .append(RcDoc::hardline())
+    }))
+}
+
+/// Render `p` with backend `b` and write it to `file`, laid out according
+/// to `cfg` (width, F* pragma options, indentation).
+pub fn translate_and_write_to_file<B: Backend>(
+    b: &B,
+    sess: &Session,
+    p: &Program,
+    file: &str,
+    typ_dict: &TypeDict,
+    cfg: &FormatConfig,
+) {
+    let file = file.trim();
+    let path = std::path::Path::new(file);
+    let mut out_file = match std::fs::File::create(&path) {
+        Err(why) => {
+            sess.err(format!("Unable to write to outuput file {}: \"{}\"", file, why).as_str());
+            return;
+        }
+        Ok(out_file) => out_file,
+    };
+    let mut w = Vec::new();
+    let module_name = path.file_stem().unwrap().to_str().unwrap();
+    use std::io::Write;
+    write!(out_file, "{}", b.module_header(module_name, cfg)).unwrap();
+    translate_program(b, p, typ_dict, cfg)
+        .render(cfg.width, &mut w)
+        .unwrap();
+    write!(out_file, "{}", String::from_utf8(w).unwrap()).unwrap()
+}
+
+/// Like [`translate_and_write_to_file`], but picks the backend from
+/// `file`'s extension instead of taking one explicitly: `.v` selects
+/// [`crate::rustspec_to_coq::CoqBackend`], `.lean` selects
+/// [`crate::rustspec_to_lean::LeanBackend`], and anything else (including
+/// no extension) falls back to [`crate::rustspec_to_fstar::FStarBackend`],
+/// matching this tool's historical default. This is the hook a CLI `-o`
+/// flag goes through to let users pick a proof assistant by output
+/// filename rather than by a separate flag.
+pub fn translate_and_write_to_file_by_extension(
+    sess: &Session,
+    p: &Program,
+    file: &str,
+    typ_dict: &TypeDict,
+    cfg: &FormatConfig,
+) {
+    let extension = std::path::Path::new(file.trim())
+        .extension()
+        .and_then(|e| e.to_str());
+    match extension {
+        Some("v") => crate::rustspec_to_coq::translate_and_write_to_file(sess, p, file, typ_dict, cfg),
+        Some("lean") => {
+            crate::rustspec_to_lean::translate_and_write_to_file(sess, p, file, typ_dict, cfg)
+        }
+        _ => crate::rustspec_to_fstar::translate_and_write_to_file(sess, p, file, typ_dict, cfg),
+    }
+}
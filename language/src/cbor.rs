@@ -0,0 +1,66 @@
+//! CBOR (de)serialization of the post-typecheck `rustspec` AST, so a spec
+//! can be typechecked once and then fed to any [`Backend`](crate::backend::Backend)
+//! (or cached across runs) without re-parsing the original Rust.
+//!
+//! This follows dhall_rust's `binary.rs` phase: the core expression tree
+//! gets a stable binary encoding used as both an interchange format and a
+//! cache. [`encode`]/[`decode`] are thin `serde_cbor` wrappers, written on
+//! the assumption that `rustspec`'s AST types derive `Serialize`/
+//! `Deserialize` -- that derive isn't added anywhere in this tree yet
+//! (`language/src/rustspec.rs` isn't part of it), so this module doesn't
+//! compile on its own; it's the serialization half of a change that also
+//! needs those derives landed on the AST types.
+//!
+//! The one wrinkle once that lands is `Spanned<T>`: its `Span` half only
+//! exists to drive diagnostics during parsing/typechecking and has no
+//! meaning once those passes are done, so it should not round-trip.
+//! `rustspec::Spanned` will need its span field `#[serde(skip)]`;
+//! encoding then drops it entirely, and `Span`'s `Default` impl (the
+//! dummy, zero-width span used by synthesized code elsewhere in the
+//! typechecker) is what decoding fills it back in with. That makes the
+//! round trip lossless modulo source positions, which is exactly what a
+//! cache or an inter-backend handoff needs: an error reported against a
+//! decoded tree just won't be able to point back at the original source.
+
+use crate::rustspec::Program;
+use crate::typechecker::TypeDict;
+use serde::{Deserialize, Serialize};
+
+/// Encode `item` to a stable binary CBOR representation.
+pub fn encode<T: Serialize>(item: &T) -> Vec<u8> {
+    serde_cbor::to_vec(item).expect("rustspec AST should always be CBOR-encodable")
+}
+
+/// Decode bytes produced by [`encode`] back into `T`.
+///
+/// Panics on malformed or incompatible input: callers only ever feed this
+/// the output of [`encode`] (from this build or a cache written by a
+/// compatible one), so a failure here means a corrupt cache entry rather
+/// than recoverable user error.
+pub fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> T {
+    serde_cbor::from_slice(bytes).expect("corrupt or version-mismatched cbor cache entry")
+}
+
+/// A [`Program`] together with the [`TypeDict`] its typechecking pass
+/// produced -- the unit a backend actually needs, and so the unit this
+/// module caches/hands off as a whole.
+#[derive(Serialize, Deserialize)]
+pub struct CheckedProgram {
+    pub program: Program,
+    pub typ_dict: TypeDict,
+}
+
+/// Encode a typechecked `(program, typ_dict)` pair to CBOR.
+pub fn encode_checked_program(program: &Program, typ_dict: &TypeDict) -> Vec<u8> {
+    encode(&CheckedProgram {
+        program: program.clone(),
+        typ_dict: typ_dict.clone(),
+    })
+}
+
+/// Decode a `(program, typ_dict)` pair produced by
+/// [`encode_checked_program`].
+pub fn decode_checked_program(bytes: &[u8]) -> (Program, TypeDict) {
+    let checked: CheckedProgram = decode(bytes);
+    (checked.program, checked.typ_dict)
+}
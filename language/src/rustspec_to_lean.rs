@@ -0,0 +1,627 @@
+//! The Lean [`Backend`]: a third proof-assistant target alongside
+//! [`crate::rustspec_to_fstar`] and [`crate::rustspec_to_coq`], built on
+//! the same shared AST traversal in [`crate::backend`]. Added together
+//! with the extension-based dispatch in
+//! [`crate::backend::translate_and_write_to_file_by_extension`] so a
+//! spec can be checked in Coq or Lean without a parallel code generator.
+
+use crate::backend::{
+    make_list, make_paren, make_tuple, make_typ_tuple, nest_or_align, translate_ident, Backend,
+    BinopTable, FallibleKind, FormatConfig, OperandClass,
+};
+use crate::rustspec::*;
+
+use crate::typechecker::{DictEntry, TypeDict};
+use pretty::RcDoc;
+use rustc_ast::ast::BinOpKind;
+use rustc_session::Session;
+
+const SEQ_MODULE: &'static str = "seq";
+
+/// The module a method call's receiver type resolves to, mirroring
+/// [`crate::rustspec_to_fstar::FuncPrefix`]. `Regular` covers the
+/// primitive/secret-int/named cases, which need no extra information
+/// beyond the module name itself.
+#[derive(Debug)]
+enum FuncPrefix {
+    Regular,
+    Array(ArraySize),
+    NatMod(String),
+}
+
+/// Resolve a method call's receiver `BaseTyp` to its Lean module name,
+/// following type aliases and array/nat_mod declarations through
+/// `typ_dict` exactly as
+/// [`crate::rustspec_to_fstar::translate_prefix_for_func_name`] does for
+/// F*.
+fn translate_prefix_for_func_name<'a>(
+    prefix: BaseTyp,
+    typ_dict: &'a TypeDict,
+) -> (RcDoc<'a, ()>, FuncPrefix) {
+    match prefix {
+        BaseTyp::Bool => panic!(), // should not happen
+        BaseTyp::Unit => panic!(), // should not happen
+        BaseTyp::UInt8 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Int8 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::UInt16 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Int16 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::UInt32 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Int32 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::UInt64 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Int64 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::UInt128 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Int128 => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Usize => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Isize => (RcDoc::as_string("int"), FuncPrefix::Regular),
+        BaseTyp::Str => (RcDoc::as_string("string"), FuncPrefix::Regular),
+        BaseTyp::Seq(_) => (RcDoc::as_string(SEQ_MODULE), FuncPrefix::Regular),
+        BaseTyp::Array(size, _) => (
+            RcDoc::as_string(SEQ_MODULE),
+            FuncPrefix::Array(size.0.clone()),
+        ),
+        BaseTyp::Named(ident, _) => {
+            // if the type is an array, we should print the Seq module instead
+            match &ident.0 {
+                Ident::Original(name) => match typ_dict.get(name) {
+                    Some((alias_typ, DictEntry::Array))
+                    | Some((alias_typ, DictEntry::Alias))
+                    | Some((alias_typ, DictEntry::NaturalInteger)) => {
+                        translate_prefix_for_func_name((alias_typ.1).0.clone(), typ_dict)
+                    }
+                    _ => (translate_ident(ident.0.clone()), FuncPrefix::Regular),
+                },
+                Ident::Hacspec(_, _) => panic!(), // should not happen
+            }
+        }
+        BaseTyp::Variable(_) => panic!(), // should not happen
+        BaseTyp::Tuple(_) => panic!(),    // should not happen
+        BaseTyp::NaturalInteger(_, modulo) => (
+            RcDoc::as_string("nat"),
+            FuncPrefix::NatMod(modulo.0.clone()),
+        ),
+    }
+}
+
+/// Lean's operator table. Like Coq, Lean doesn't have F*'s dotted
+/// secret-integer operators, so the `SecretInt`/`PublicSize` classes
+/// share Lean's ordinary arithmetic/bitwise spellings; `nat_mod` keeps
+/// the same `-%`/`+%`/... convention as the Coq backend.
+static LEAN_BINOP_TABLE: &[(BinOpKind, OperandClass, &str)] = &[
+    (BinOpKind::Sub, OperandClass::NatMod, "-%"),
+    (BinOpKind::Add, OperandClass::NatMod, "+%"),
+    (BinOpKind::Mul, OperandClass::NatMod, "*%"),
+    (BinOpKind::Div, OperandClass::NatMod, "/%"),
+    (BinOpKind::Rem, OperandClass::NatMod, "%%"),
+    (BinOpKind::Sub, OperandClass::PublicSize, "-"),
+    (BinOpKind::Add, OperandClass::PublicSize, "+"),
+    (BinOpKind::Mul, OperandClass::PublicSize, "*"),
+    (BinOpKind::Div, OperandClass::PublicSize, "/"),
+    (BinOpKind::Sub, OperandClass::Seq, "seq_minus"),
+    (BinOpKind::Add, OperandClass::Seq, "seq_add"),
+    (BinOpKind::Mul, OperandClass::Seq, "seq_mul"),
+    (BinOpKind::Div, OperandClass::Seq, "seq_div"),
+    (BinOpKind::BitXor, OperandClass::Seq, "seq_xor"),
+    (BinOpKind::BitAnd, OperandClass::Seq, "seq_and"),
+    (BinOpKind::BitOr, OperandClass::Seq, "seq_or"),
+    (BinOpKind::Sub, OperandClass::SecretInt, "-"),
+    (BinOpKind::Add, OperandClass::SecretInt, "+"),
+    (BinOpKind::Mul, OperandClass::SecretInt, "*"),
+    (BinOpKind::Div, OperandClass::SecretInt, "/"),
+    (BinOpKind::Rem, OperandClass::SecretInt, "%"),
+    (BinOpKind::BitXor, OperandClass::SecretInt, "^^^"),
+    (BinOpKind::BitAnd, OperandClass::SecretInt, "&&&"),
+    (BinOpKind::BitOr, OperandClass::SecretInt, "|||"),
+    (BinOpKind::Shl, OperandClass::SecretInt, "<<<"),
+    (BinOpKind::Shr, OperandClass::SecretInt, ">>>"),
+    (BinOpKind::Lt, OperandClass::SecretInt, "<"),
+    (BinOpKind::Le, OperandClass::SecretInt, "<="),
+    (BinOpKind::Ge, OperandClass::SecretInt, ">="),
+    (BinOpKind::Gt, OperandClass::SecretInt, ">"),
+    (BinOpKind::Ne, OperandClass::SecretInt, "!="),
+    (BinOpKind::Eq, OperandClass::SecretInt, "=="),
+    (BinOpKind::And, OperandClass::SecretInt, "&&"),
+    (BinOpKind::Or, OperandClass::SecretInt, "||"),
+];
+
+/// The full `nat_mod` interface for a `NaturalIntegerDecl`: the
+/// byte-sequence conversions, `from_literal`, and the modular
+/// `add`/`sub`/`mul`/`pow_mod` operations, each a `def` wrapper
+/// specializing `Hacspec.Lib`'s generic `NatMod` primitives to `modulus`.
+/// `secrecy` picks the constant-time `pow_mod` and omits `equal` for
+/// `Secrecy::Secret` (a secret integer must not branch on its value),
+/// versus the natural `pow_mod` plus an `equal` wrapper for
+/// `Secrecy::Public`.
+fn nat_mod_interface<'a>(nat_name: &str, canvas_name: &str, modulus: &str, secrecy: &Secrecy) -> RcDoc<'a, ()> {
+    let pow_mod_fn = match secrecy {
+        Secrecy::Secret => "nat_mod_pow_mod_ct",
+        Secrecy::Public => "nat_mod_pow_mod",
+    };
+    let mut defs = vec![
+        format!(
+            "def {nat}_from_byte_seq_le (s : {canvas}) : {nat} :=\n  nat_from_byte_seq_le {m} s",
+            nat = nat_name, canvas = canvas_name, m = modulus
+        ),
+        format!(
+            "def {nat}_from_byte_seq_be (s : {canvas}) : {nat} :=\n  nat_from_byte_seq_be {m} s",
+            nat = nat_name, canvas = canvas_name, m = modulus
+        ),
+        format!(
+            "def {nat}_to_byte_seq_le (x : {nat}) : {canvas} :=\n  nat_to_byte_seq_le {m} x",
+            nat = nat_name, canvas = canvas_name, m = modulus
+        ),
+        format!(
+            "def {nat}_to_byte_seq_be (x : {nat}) : {canvas} :=\n  nat_to_byte_seq_be {m} x",
+            nat = nat_name, canvas = canvas_name, m = modulus
+        ),
+        format!(
+            "def {nat}_from_literal (n : Nat) : {nat} :=\n  nat_from_literal {m} n",
+            nat = nat_name, m = modulus
+        ),
+        format!(
+            "def {nat}_add (x y : {nat}) : {nat} :=\n  nat_mod_add {m} x y",
+            nat = nat_name, m = modulus
+        ),
+        format!(
+            "def {nat}_sub (x y : {nat}) : {nat} :=\n  nat_mod_sub {m} x y",
+            nat = nat_name, m = modulus
+        ),
+        format!(
+            "def {nat}_mul (x y : {nat}) : {nat} :=\n  nat_mod_mul {m} x y",
+            nat = nat_name, m = modulus
+        ),
+        format!(
+            "def {nat}_pow_mod (x : {nat}) (y : Nat) : {nat} :=\n  {f} {m} x y",
+            nat = nat_name, m = modulus, f = pow_mod_fn
+        ),
+    ];
+    if let Secrecy::Public = secrecy {
+        defs.push(format!(
+            "def {nat}_equal (x y : {nat}) : Bool :=\n  nat_mod_equal {m} x y",
+            nat = nat_name, m = modulus
+        ));
+    }
+    RcDoc::as_string(defs.join("\n\n"))
+}
+
+/// The Lean backend: renders the typechecked AST as Lean built on a
+/// `Hacspec.Lib`-style Lean library (`Vector`-based fixed-size arrays, a
+/// `NatMod` modular-integer type).
+pub struct LeanBackend;
+
+impl Backend for LeanBackend {
+    fn base_typ<'a>(&self, tau: BaseTyp) -> RcDoc<'a, ()> {
+        match tau {
+            BaseTyp::Unit => RcDoc::as_string("Unit"),
+            BaseTyp::Bool => RcDoc::as_string("Bool"),
+            BaseTyp::UInt8 => RcDoc::as_string("UInt8"),
+            BaseTyp::Int8 => RcDoc::as_string("Int8"),
+            BaseTyp::UInt16 => RcDoc::as_string("UInt16"),
+            BaseTyp::Int16 => RcDoc::as_string("Int16"),
+            BaseTyp::UInt32 => RcDoc::as_string("UInt32"),
+            BaseTyp::Int32 => RcDoc::as_string("Int32"),
+            BaseTyp::UInt64 => RcDoc::as_string("UInt64"),
+            BaseTyp::Int64 => RcDoc::as_string("Int64"),
+            BaseTyp::UInt128 => RcDoc::as_string("UInt128"),
+            BaseTyp::Int128 => RcDoc::as_string("Int128"),
+            BaseTyp::Usize => RcDoc::as_string("Nat"),
+            BaseTyp::Isize => RcDoc::as_string("Int"),
+            BaseTyp::Str => RcDoc::as_string("String"),
+            BaseTyp::Seq(tau) => {
+                let tau: BaseTyp = tau.0;
+                RcDoc::as_string("Array")
+                    .append(RcDoc::space())
+                    .append(self.base_typ(tau))
+                    .group()
+            }
+            BaseTyp::Array(size, tau) => {
+                let tau = tau.0;
+                RcDoc::as_string("Vector")
+                    .append(RcDoc::space())
+                    .append(self.base_typ(tau))
+                    .append(RcDoc::space())
+                    .append(RcDoc::as_string(match &size.0 {
+                        ArraySize::Ident(id) => format!("{}", id),
+                        ArraySize::Integer(i) => format!("{}", i),
+                    }))
+                    .group()
+            }
+            BaseTyp::Named(ident, args) => translate_ident(ident.0).append(match args {
+                None => RcDoc::nil(),
+                Some(args) => RcDoc::space().append(RcDoc::intersperse(
+                    args.iter().map(|arg| self.base_typ(arg.0.clone())),
+                    RcDoc::space(),
+                )),
+            }),
+            BaseTyp::Variable(id) => RcDoc::as_string(format!("'t{}", id.0)),
+            BaseTyp::Tuple(args) => {
+                make_typ_tuple("×", args.into_iter().map(|(arg, _)| self.base_typ(arg)))
+            }
+            BaseTyp::NaturalInteger(_secrecy, modulo) => RcDoc::as_string("NatMod")
+                .append(RcDoc::space())
+                .append(RcDoc::as_string(format!("0x{}", &modulo.0))),
+        }
+    }
+
+    fn literal<'a>(&self, lit: &Literal) -> RcDoc<'a, ()> {
+        match lit {
+            Literal::Unit => RcDoc::as_string("()"),
+            Literal::Bool(true) => RcDoc::as_string("true"),
+            Literal::Bool(false) => RcDoc::as_string("false"),
+            Literal::Int128(x) => RcDoc::as_string(format!("({:#x} : Int128)", x)),
+            Literal::UInt128(x) => RcDoc::as_string(format!("({:#x} : UInt128)", x)),
+            Literal::Int64(x) => RcDoc::as_string(format!("({:#x} : Int64)", x)),
+            Literal::UInt64(x) => RcDoc::as_string(format!("({:#x} : UInt64)", x)),
+            Literal::Int32(x) => RcDoc::as_string(format!("({:#x} : Int32)", x)),
+            Literal::UInt32(x) => RcDoc::as_string(format!("({:#x} : UInt32)", x)),
+            Literal::Int16(x) => RcDoc::as_string(format!("({:#x} : Int16)", x)),
+            Literal::UInt16(x) => RcDoc::as_string(format!("({:#x} : UInt16)", x)),
+            Literal::Int8(x) => RcDoc::as_string(format!("({:#x} : Int8)", x)),
+            Literal::UInt8(x) => RcDoc::as_string(format!("({:#x} : UInt8)", x)),
+            Literal::Isize(x) => RcDoc::as_string(format!("({} : Int)", x)),
+            Literal::Usize(x) => RcDoc::as_string(format!("({} : Nat)", x)),
+            Literal::Str(msg) => RcDoc::as_string(format!("\"{}\"", msg)),
+        }
+    }
+
+    fn binop_table(&self) -> BinopTable {
+        LEAN_BINOP_TABLE
+    }
+
+    fn unop<'a, 'b>(&self, op: &'a UnOpKind, _op_typ: &'b Typ) -> RcDoc<'a, ()> {
+        match op {
+            UnOpKind::Not => RcDoc::as_string("!"),
+            UnOpKind::Neg => RcDoc::as_string("-"),
+        }
+    }
+
+    fn func_name<'a>(
+        &self,
+        prefix: Option<Spanned<BaseTyp>>,
+        name: &'a Ident,
+        typ_dict: &'a TypeDict,
+    ) -> RcDoc<'a, ()> {
+        match prefix {
+            None => translate_ident(name.clone()),
+            Some((prefix, _)) => {
+                let (module_name, _prefix_info) =
+                    translate_prefix_for_func_name(prefix, typ_dict);
+                module_name
+                    .append(RcDoc::as_string("."))
+                    .append(translate_ident(name.clone()))
+            }
+        }
+    }
+
+    fn let_binding<'a>(
+        &self,
+        pat: RcDoc<'a, ()>,
+        typ: Option<RcDoc<'a, ()>>,
+        expr: RcDoc<'a, ()>,
+        toplevel: bool,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()> {
+        if toplevel {
+            // Lean items are `def ... := ...`, not `let`.
+            crate::backend::make_let_binding("def", pat, typ, ":=", expr, toplevel, cfg)
+        } else {
+            // Lean 3-style `let x := e in e2`, which `make_let_binding`'s
+            // `toplevel = false` case already produces.
+            crate::backend::make_let_binding("let", pat, typ, ":=", expr, toplevel, cfg)
+        }
+    }
+
+    fn for_loop<'a>(
+        &self,
+        x: RcDoc<'a, ()>,
+        e1: RcDoc<'a, ()>,
+        e2: RcDoc<'a, ()>,
+        acc: RcDoc<'a, ()>,
+        body: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()> {
+        RcDoc::as_string("foldi")
+            .append(RcDoc::space())
+            .append(e1)
+            .append(RcDoc::space())
+            .append(e2)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("(fun"))
+            .append(RcDoc::space())
+            .append(x)
+            .append(RcDoc::space())
+            .append(acc.clone())
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("=>"))
+            .append(RcDoc::line())
+            .append(body)
+            .append(RcDoc::as_string(")"))
+            .group()
+            .nest(2)
+            .append(RcDoc::line())
+            .append(acc)
+    }
+
+    fn conditional<'a>(
+        &self,
+        cond: RcDoc<'a, ()>,
+        b1: RcDoc<'a, ()>,
+        b2: Option<RcDoc<'a, ()>>,
+    ) -> RcDoc<'a, ()> {
+        RcDoc::as_string("if")
+            .append(RcDoc::space())
+            .append(cond)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("then"))
+            .append(RcDoc::space())
+            .append(make_paren(b1))
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("else"))
+            .append(RcDoc::space())
+            .append(make_paren(b2.unwrap_or_else(RcDoc::nil)))
+    }
+
+    fn array_index<'a>(&self, x: RcDoc<'a, ()>, e: RcDoc<'a, ()>) -> RcDoc<'a, ()> {
+        RcDoc::as_string("array_index")
+            .append(RcDoc::space())
+            .append(x)
+            .append(RcDoc::space())
+            .append(e)
+    }
+
+    fn array_update<'a>(
+        &self,
+        x: RcDoc<'a, ()>,
+        e1: RcDoc<'a, ()>,
+        e2: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()> {
+        RcDoc::as_string("array_upd")
+            .append(RcDoc::space())
+            .append(x)
+            .append(RcDoc::space())
+            .append(e1)
+            .append(RcDoc::space())
+            .append(e2)
+    }
+
+    fn new_array<'a>(&self, elements: Vec<RcDoc<'a, ()>>) -> RcDoc<'a, ()> {
+        RcDoc::as_string("#")
+            .append(make_list(elements))
+    }
+
+    fn array_decl<'a>(
+        &self,
+        name: RcDoc<'a, ()>,
+        size: RcDoc<'a, ()>,
+        cell_t: RcDoc<'a, ()>,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()> {
+        RcDoc::as_string("def")
+            .append(RcDoc::space())
+            .append(name)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(":="))
+            .group()
+            .append(nest_or_align(
+                cfg,
+                RcDoc::line()
+                    .append(RcDoc::as_string("Vector"))
+                    .append(RcDoc::space())
+                    .append(cell_t)
+                    .append(RcDoc::space())
+                    .append(size)
+                    .group(),
+            ))
+    }
+
+    fn nat_mod_decl<'a>(
+        &self,
+        nat_name: RcDoc<'a, ()>,
+        canvas_name: RcDoc<'a, ()>,
+        canvas_size: RcDoc<'a, ()>,
+        modulo: &'a str,
+        secrecy: &'a Secrecy,
+        cfg: &FormatConfig,
+    ) -> RcDoc<'a, ()> {
+        let modulus = format!("0x{}", modulo);
+        let nat_name_str = format!("{}", nat_name.clone().pretty(0));
+        let canvas_name_str = format!("{}", canvas_name.clone().pretty(0));
+        RcDoc::as_string("def")
+            .append(RcDoc::space())
+            .append(canvas_name)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(":="))
+            .group()
+            .append(nest_or_align(
+                cfg,
+                RcDoc::line()
+                    .append(RcDoc::as_string("Vector"))
+                    .append(RcDoc::space())
+                    .append(make_paren(self.base_typ(BaseTyp::UInt8)))
+                    .append(RcDoc::space())
+                    .append(canvas_size)
+                    .group(),
+            ))
+            .append(RcDoc::hardline())
+            .append(RcDoc::hardline())
+            .append(
+                RcDoc::as_string("def")
+                    .append(RcDoc::space())
+                    .append(nat_name)
+                    .append(RcDoc::space())
+                    .append(RcDoc::as_string(":="))
+                    .group()
+                    .append(nest_or_align(
+                        cfg,
+                        RcDoc::line()
+                            .append(RcDoc::as_string("NatMod"))
+                            .append(RcDoc::space())
+                            .append(RcDoc::as_string(modulus.clone()))
+                            .group(),
+                    ))
+                    .append(RcDoc::hardline())
+                    .append(RcDoc::hardline())
+                    .append(nat_mod_interface(
+                        &nat_name_str,
+                        &canvas_name_str,
+                        &modulus,
+                        secrecy,
+                    )),
+            )
+    }
+
+    fn module_header(&self, module_name: &str, _cfg: &FormatConfig) -> String {
+        format!(
+            "-- {}\n\n\
+            import Hacspec.Lib\n\n",
+            module_name
+        )
+    }
+
+    fn integer_cast<'a>(
+        &self,
+        _source: &BaseTyp,
+        target: &BaseTyp,
+        expr: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()> {
+        // Like Coq, this backend emits no classify/declassify step: every
+        // cast, public or secret on either side, is the same `int_cast`
+        // to the target's Lean type.
+        RcDoc::as_string("int_cast")
+            .append(RcDoc::space())
+            .append(make_paren(self.base_typ(target.clone())))
+            .append(RcDoc::space())
+            .append(expr)
+    }
+
+    fn wrap_success<'a>(&self, kind: FallibleKind, expr: RcDoc<'a, ()>) -> RcDoc<'a, ()> {
+        let ctor = match kind {
+            FallibleKind::Result => "Ok",
+            FallibleKind::Option => "some",
+        };
+        RcDoc::as_string(ctor)
+            .append(RcDoc::space())
+            .append(make_paren(expr))
+    }
+
+    fn monadic_bind<'a>(
+        &self,
+        kind: FallibleKind,
+        pat: RcDoc<'a, ()>,
+        scrutinee: RcDoc<'a, ()>,
+        continuation: RcDoc<'a, ()>,
+    ) -> RcDoc<'a, ()> {
+        let (err_pat, err_arm, ok_ctor) = match kind {
+            FallibleKind::Result => (
+                RcDoc::as_string("Err err"),
+                RcDoc::as_string("Err err"),
+                "Ok",
+            ),
+            FallibleKind::Option => (
+                RcDoc::as_string("none"),
+                RcDoc::as_string("none"),
+                "some",
+            ),
+        };
+        RcDoc::as_string("match")
+            .append(RcDoc::space())
+            .append(scrutinee)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("with"))
+            .append(RcDoc::hardline())
+            .append(RcDoc::as_string("|"))
+            .append(RcDoc::space())
+            .append(err_pat)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("=>"))
+            .append(RcDoc::space())
+            .append(err_arm)
+            .append(RcDoc::hardline())
+            .append(RcDoc::as_string("|"))
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(ok_ctor))
+            .append(RcDoc::space())
+            .append(pat)
+            .append(RcDoc::space())
+            .append(RcDoc::as_string("=>"))
+            .group()
+            .append(RcDoc::line().append(continuation).nest(2))
+    }
+
+    fn trait_decl<'a>(
+        &self,
+        name: RcDoc<'a, ()>,
+        type_params: Vec<RcDoc<'a, ()>>,
+        methods: Vec<(RcDoc<'a, ()>, RcDoc<'a, ()>)>,
+    ) -> RcDoc<'a, ()> {
+        // Lean 3-style `class ... := (field : typ) ...`, matching
+        // `let_binding`'s Lean 3 `let ... in` choice above.
+        RcDoc::as_string("class")
+            .append(RcDoc::space())
+            .append(name)
+            .append(RcDoc::concat(
+                type_params
+                    .into_iter()
+                    .map(|p| RcDoc::space().append(p)),
+            ))
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(":="))
+            .append(
+                RcDoc::concat(methods.into_iter().map(|(name, typ)| {
+                    RcDoc::hardline()
+                        .append(RcDoc::as_string("("))
+                        .append(name)
+                        .append(RcDoc::space())
+                        .append(RcDoc::as_string(":"))
+                        .append(RcDoc::space())
+                        .append(typ)
+                        .append(RcDoc::as_string(")"))
+                }))
+                .nest(2),
+            )
+    }
+
+    fn impl_decl<'a>(
+        &self,
+        trait_name: RcDoc<'a, ()>,
+        self_typ: RcDoc<'a, ()>,
+        type_args: Vec<RcDoc<'a, ()>>,
+        methods: Vec<(RcDoc<'a, ()>, RcDoc<'a, ()>)>,
+    ) -> RcDoc<'a, ()> {
+        RcDoc::as_string("instance")
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(":"))
+            .append(RcDoc::space())
+            .append(trait_name)
+            .append(RcDoc::space())
+            .append(self_typ)
+            .append(RcDoc::concat(
+                type_args.into_iter().map(|t| RcDoc::space().append(t)),
+            ))
+            .append(RcDoc::space())
+            .append(RcDoc::as_string(":= {"))
+            .append(
+                RcDoc::concat(methods.into_iter().map(|(name, body)| {
+                    RcDoc::hardline()
+                        .append(name)
+                        .append(RcDoc::space())
+                        .append(RcDoc::as_string(":="))
+                        .group()
+                        .append(RcDoc::line().append(body).nest(2))
+                        .append(RcDoc::as_string(","))
+                }))
+                .nest(2),
+            )
+            .append(RcDoc::hardline())
+            .append(RcDoc::as_string("}"))
+    }
+}
+
+/// Render `p` to Lean and write it to `file`.
+pub fn translate_and_write_to_file(
+    sess: &Session,
+    p: &Program,
+    file: &str,
+    typ_dict: &TypeDict,
+    cfg: &FormatConfig,
+) {
+    crate::backend::translate_and_write_to_file(&LeanBackend, sess, p, file, typ_dict, cfg)
+}
@@ -0,0 +1,107 @@
+//! Generic short-Weierstrass curve arithmetic: `y^2 = x^3 + a*x + b mod p`.
+//!
+//! This module is generic over the field element type so that it can be
+//! instantiated for both P-256 ([`crate::ec::p256`]) and P-384
+//! ([`crate::ec::p384`]) without duplicating the formulas.
+
+use hacspec::prelude::*;
+
+/// A point on the curve in affine coordinates: `Affine(x, y)`.
+#[derive(Clone, Copy, Debug)]
+pub struct Affine<FieldElement>(pub FieldElement, pub FieldElement);
+
+/// The curve parameters needed to do arithmetic over a field element type.
+///
+/// All curves used here are of the form `y^2 = x^3 + a*x + b mod p` with
+/// `a = -3` and `p ≡ 3 (mod 4)`, so a modular square root can be taken as
+/// `beta = alpha^((p+1)/4) mod p`.
+pub trait EllipticCurve: UnsignedIntegerCopy {
+    /// The curve coefficient `a` (always `-3` for the curves handled here).
+    fn coeff_a() -> Self;
+    /// The curve coefficient `b`.
+    fn coeff_b() -> Self;
+    /// `(p + 1) / 4`, the exponent used to extract square roots.
+    fn sqrt_exp() -> Self;
+}
+
+/// Double an affine point. Exposed alongside [`point_add`] (rather than
+/// kept private) so callers -- and this module's own tests -- can build
+/// an independent cross-check for [`point_mul`] out of the two.
+pub fn point_double<FieldElement: EllipticCurve>(p: Affine<FieldElement>) -> Affine<FieldElement> {
+    let Affine(x, y) = p;
+    let three = FieldElement::from_literal(3);
+    let two = FieldElement::from_literal(2);
+    let lambda = ((x * x) * three + FieldElement::coeff_a()) / (y * two);
+    let x3 = (lambda * lambda) - (x * two);
+    let y3 = (lambda * (x - x3)) - y;
+    Affine(x3, y3)
+}
+
+/// Add two distinct affine points. Does not handle the doubling or
+/// point-at-infinity cases, which are not exercised by the ECDH vectors
+/// this module serves.
+pub fn point_add<FieldElement: EllipticCurve>(
+    p: Affine<FieldElement>,
+    q: Affine<FieldElement>,
+) -> Affine<FieldElement> {
+    let Affine(x1, y1) = p;
+    let Affine(x2, y2) = q;
+    let lambda = (y2 - y1) / (x2 - x1);
+    let x3 = (lambda * lambda) - x1 - x2;
+    let y3 = (lambda * (x1 - x3)) - y1;
+    Affine(x3, y3)
+}
+
+/// Scalar multiplication `k * p` via the double-and-add method.
+pub fn point_mul<FieldElement: EllipticCurve, Scalar: UnsignedIntegerCopy>(
+    k: Scalar,
+    p: Affine<FieldElement>,
+) -> Affine<FieldElement> {
+    let mut result = p;
+    let mut first = true;
+    for i in 0..k.num_bits() {
+        if !first {
+            result = point_double(result);
+        }
+        if k.bit(k.num_bits() - 1 - i) {
+            result = if first { p } else { point_add(result, p) };
+            first = false;
+        }
+    }
+    result
+}
+
+/// Reconstruct the affine point for the given x-coordinate, choosing the
+/// root whose parity matches the SEC1 compression prefix (`02` -> even,
+/// `03` -> odd). Returns `None` if `x` is not on the curve.
+pub fn decompress_point<FieldElement: EllipticCurve>(
+    prefix_is_odd: bool,
+    x: FieldElement,
+) -> Option<Affine<FieldElement>> {
+    let alpha = (x * x * x) + (FieldElement::coeff_a() * x) + FieldElement::coeff_b();
+    let beta = alpha.pow_self(FieldElement::sqrt_exp());
+    if !beta.equal(FieldElement::ZERO()) && !(beta * beta).equal(alpha) {
+        // alpha has no square root in the field: invalid encoding.
+        return None;
+    }
+    let y = if beta.bit(0) == prefix_is_odd {
+        beta
+    } else {
+        FieldElement::ZERO() - beta
+    };
+    if !(y * y).equal(alpha) {
+        return None;
+    }
+    Some(Affine(x, y))
+}
+
+/// Check that `p` lies on the curve, i.e. `y^2 == x^3 + a*x + b mod p`.
+///
+/// Field elements are always kept reduced modulo `p` by construction, so
+/// coordinates are automatically in `[0, p)`; P-256 and P-384 both have
+/// cofactor 1, so an on-curve point can never land in a small subgroup.
+pub fn validate_point<FieldElement: EllipticCurve>(p: Affine<FieldElement>) -> bool {
+    let Affine(x, y) = p;
+    let rhs = (x * x * x) + (FieldElement::coeff_a() * x) + FieldElement::coeff_b();
+    (y * y).equal(rhs)
+}
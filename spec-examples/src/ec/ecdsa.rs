@@ -0,0 +1,63 @@
+//! ECDSA signing and verification over the short-Weierstrass curves in
+//! [`crate::ec::p256`] and [`crate::ec::p384`], built on
+//! [`arithmetic::point_mul`] and [`arithmetic::point_add`].
+//!
+//! The generator `G` and the curve order `n` are curve properties, not
+//! arithmetic ones, so (mirroring [`arithmetic::point_mul`]'s `Scalar`
+//! parameter) callers pass `G` in explicitly rather than this module
+//! reaching into a trait for it.
+
+use hacspec::prelude::*;
+
+use crate::ec::arithmetic::{self, Affine, EllipticCurve};
+
+/// Sign a (already curve-order-reduced) message hash with private key `d`
+/// and per-signature nonce `k`, returning `(r, s)`.
+///
+/// `k` must be a fresh, secret, uniformly random nonce for every
+/// signature: reusing `k` across two signatures leaks `d`. Generating
+/// such a `k` is outside the scope of this spec.
+pub fn ecdsa_sign<FieldElement, Scalar>(
+    g: Affine<FieldElement>,
+    d: Scalar,
+    msg_hash: Scalar,
+    k: Scalar,
+) -> (Scalar, Scalar)
+where
+    FieldElement: EllipticCurve,
+    Scalar: UnsignedIntegerCopy,
+{
+    let r_point = arithmetic::point_mul(k, g);
+    let r = Scalar::from_byte_seq_be(&r_point.0.to_byte_seq_be());
+    let s = (msg_hash + (r * d)) / k;
+    (r, s)
+}
+
+/// Verify that `(r, s)` is a valid signature of `msg_hash` under public
+/// key `q = d*G`, rejecting `r, s` outside `[1, n-1]`.
+///
+/// Wycheproof-style harnesses must reject the signature *before* calling
+/// this function if the DER-encoded `r`/`s` integers are `>= n`, since
+/// `Scalar` (being a `nat_mod` type) always stores a value already
+/// reduced mod `n` and so cannot distinguish `r` from `r + n`.
+pub fn ecdsa_verify<FieldElement, Scalar>(
+    g: Affine<FieldElement>,
+    q: Affine<FieldElement>,
+    msg_hash: Scalar,
+    signature: (Scalar, Scalar),
+) -> bool
+where
+    FieldElement: EllipticCurve,
+    Scalar: UnsignedIntegerCopy,
+{
+    let (r, s) = signature;
+    if r.equal(Scalar::ZERO()) || s.equal(Scalar::ZERO()) {
+        return false;
+    }
+    let s_inv = Scalar::ONE() / s;
+    let u1 = msg_hash * s_inv;
+    let u2 = r * s_inv;
+    let r_point = arithmetic::point_add(arithmetic::point_mul(u1, g), arithmetic::point_mul(u2, q));
+    let r_check = Scalar::from_byte_seq_be(&r_point.0.to_byte_seq_be());
+    r_check.equal(r)
+}
@@ -0,0 +1,164 @@
+//! Hash-to-curve (RFC 9380) for the short-Weierstrass curves in
+//! [`crate::ec::p256`] and [`crate::ec::p384`], via `expand_message_xmd`
+//! (generic over the suite's hash function, RFC 9380 appendix C) and the
+//! simplified SWU map (RFC 9380 §6.6.2).
+//!
+//! Only the full encodings are public: `encode_to_curve` (non-uniform)
+//! and `hash_to_curve` (random-oracle); `map_to_curve` on its own is
+//! private.
+
+use hacspec::prelude::*;
+
+use crate::ec::arithmetic::{point_add, Affine, EllipticCurve};
+
+/// Curve parameters needed, on top of [`EllipticCurve`], to hash onto the
+/// curve: the simplified-SWU non-square constant `Z`, `L` (the number of
+/// pseudorandom bytes `expand_message_xmd` must produce per field element,
+/// RFC 9380 §5, `ceil((ceil(log2(p)) + k) / 8)` for the curve's
+/// recommended k-bit security margin), and the suite's hash function
+/// (appendix C: SHA-256 for P-256, SHA-384 for P-384).
+pub trait HashToCurve: EllipticCurve {
+    /// The simplified-SWU non-square constant `Z`.
+    fn z() -> Self;
+    /// `L`, the number of bytes `expand_message_xmd` produces per field
+    /// element hashed onto this curve.
+    fn l() -> usize;
+    /// The suite's hash function, applied to a single input block.
+    fn expand_message_hash(input: &ByteSeq) -> ByteSeq;
+    /// `b_in_bytes`, [`Self::expand_message_hash`]'s output size.
+    fn hash_output_bytes() -> usize;
+    /// `s_in_bytes`, [`Self::expand_message_hash`]'s input block size.
+    fn hash_block_bytes() -> usize;
+}
+
+/// `I2OSP(value, length)`: `value` as a big-endian byte string of `length`
+/// bytes (RFC 8017 §4.1).
+fn i2osp(value: usize, length: usize) -> ByteSeq {
+    let mut out = ByteSeq::new(length);
+    let mut v = value;
+    for i in 0..length {
+        out[length - 1 - i] = (v & 0xff) as u8;
+        v >>= 8;
+    }
+    out
+}
+
+fn xor(a: &ByteSeq, b: &ByteSeq) -> ByteSeq {
+    let mut out = ByteSeq::new(a.len());
+    for i in 0..a.len() {
+        out[i] = a[i] ^ b[i];
+    }
+    out
+}
+
+/// RFC 9380 §5.3.1 `expand_message_xmd`, generic over the suite's hash
+/// function (`FieldElement::expand_message_hash`), so each curve can use
+/// the hash its real suite specifies instead of a hardcoded one.
+fn expand_message_xmd<FieldElement: HashToCurve>(
+    msg: &ByteSeq,
+    dst: &ByteSeq,
+    len_in_bytes: usize,
+) -> ByteSeq {
+    let b_in_bytes = FieldElement::hash_output_bytes();
+    let s_in_bytes = FieldElement::hash_block_bytes();
+    let ell = (len_in_bytes + b_in_bytes - 1) / b_in_bytes;
+    let dst_prime = dst.concat(&i2osp(dst.len(), 1));
+    let z_pad = ByteSeq::new(s_in_bytes);
+    let msg_prime = z_pad
+        .concat(msg)
+        .concat(&i2osp(len_in_bytes, 2))
+        .concat(&i2osp(0, 1))
+        .concat(&dst_prime);
+
+    let b_0 = FieldElement::expand_message_hash(&msg_prime);
+    let mut b_i = FieldElement::expand_message_hash(&b_0.concat(&i2osp(1, 1)).concat(&dst_prime));
+    let mut uniform_bytes = b_i.clone();
+    for i in 2..=ell {
+        let arg = xor(&b_0, &b_i).concat(&i2osp(i, 1)).concat(&dst_prime);
+        b_i = FieldElement::expand_message_hash(&arg);
+        uniform_bytes = uniform_bytes.concat(&b_i);
+    }
+
+    let mut out = ByteSeq::new(len_in_bytes);
+    for i in 0..len_in_bytes {
+        out[i] = uniform_bytes[i];
+    }
+    out
+}
+
+/// RFC 9380 §5.2 `hash_to_field`, specialized to `count = 1`: picks the
+/// `index`th `L`-byte chunk out of `uniform_bytes` and reduces it mod `p`.
+fn hash_to_field_element<FieldElement: HashToCurve>(
+    uniform_bytes: &ByteSeq,
+    index: usize,
+) -> FieldElement {
+    let l = FieldElement::l();
+    let mut tv = ByteSeq::new(l);
+    for j in 0..l {
+        tv[j] = uniform_bytes[index * l + j];
+    }
+    FieldElement::from_byte_seq_be(&tv)
+}
+
+fn sgn0<FieldElement: EllipticCurve>(x: FieldElement) -> bool {
+    x.bit(0)
+}
+
+/// RFC 9380 §6.6.2, the simplified SWU map for curves with `a, b != 0`.
+fn map_to_curve<FieldElement: HashToCurve>(u: FieldElement) -> Affine<FieldElement> {
+    let z = FieldElement::z();
+    let a = FieldElement::coeff_a();
+    let b = FieldElement::coeff_b();
+
+    let tv1 = z * (u * u);
+    let tv2 = (tv1 * tv1) + tv1;
+    let x1 = if tv2.equal(FieldElement::ZERO()) {
+        // 1 + 1/0 is exceptional: RFC 9380 falls back to b/(Z*a).
+        b / (z * a)
+    } else {
+        (FieldElement::ZERO() - (b / a)) * (FieldElement::ONE() + (FieldElement::ONE() / tv2))
+    };
+    let gx1 = (x1 * x1 * x1) + (a * x1) + b;
+
+    let y1 = gx1.pow_self(FieldElement::sqrt_exp());
+    let gx1_is_square = (y1 * y1).equal(gx1);
+
+    let (x, y_candidate) = if gx1_is_square {
+        (x1, y1)
+    } else {
+        let x2 = tv1 * x1;
+        let gx2 = tv1 * tv1 * tv1 * gx1;
+        let y2 = gx2.pow_self(FieldElement::sqrt_exp());
+        (x2, y2)
+    };
+    let y = if sgn0(y_candidate) == sgn0(u) {
+        y_candidate
+    } else {
+        FieldElement::ZERO() - y_candidate
+    };
+    Affine(x, y)
+}
+
+/// RFC 9380 §3, `encode_to_curve`: a single `map_to_curve` call over one
+/// field element hashed from `(msg, dst)`. Unlike [`hash_to_curve`], the
+/// result is not uniformly distributed over the curve.
+pub fn encode_to_curve<FieldElement: HashToCurve>(
+    msg: &ByteSeq,
+    dst: &ByteSeq,
+) -> Affine<FieldElement> {
+    let uniform_bytes = expand_message_xmd::<FieldElement>(msg, dst, FieldElement::l());
+    let u = hash_to_field_element::<FieldElement>(&uniform_bytes, 0);
+    map_to_curve(u)
+}
+
+/// RFC 9380 §3, `hash_to_curve`: two independent `map_to_curve` calls
+/// summed with [`point_add`], giving a uniformly distributed curve point.
+pub fn hash_to_curve<FieldElement: HashToCurve>(
+    msg: &ByteSeq,
+    dst: &ByteSeq,
+) -> Affine<FieldElement> {
+    let uniform_bytes = expand_message_xmd::<FieldElement>(msg, dst, 2 * FieldElement::l());
+    let u0 = hash_to_field_element::<FieldElement>(&uniform_bytes, 0);
+    let u1 = hash_to_field_element::<FieldElement>(&uniform_bytes, 1);
+    point_add(map_to_curve(u0), map_to_curve(u1))
+}
@@ -0,0 +1,12 @@
+//! Elliptic-curve examples: short-Weierstrass arithmetic (P-256, P-384)
+//! used by the ECDH, ECDSA and hash-to-curve examples, plus the
+//! Montgomery-curve ladder used by X25519.
+
+pub mod arithmetic;
+pub mod ecdsa;
+pub mod hash_to_curve;
+pub mod p256;
+pub mod p384;
+pub mod x25519;
+
+pub use arithmetic::Affine;
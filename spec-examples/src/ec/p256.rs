@@ -0,0 +1,61 @@
+//! NIST P-256 (secp256r1) field and scalar arithmetic.
+
+use hacspec::prelude::*;
+use hacspec_sha256::sha256;
+
+use crate::ec::arithmetic::{Affine, EllipticCurve};
+use crate::ec::hash_to_curve::HashToCurve;
+
+nat_mod!(
+    type_name: FieldElement,
+    type_of_canvas: FieldCanvas,
+    bit_size_of_field: 256,
+    modulo_value: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"
+);
+
+nat_mod!(
+    type_name: Scalar,
+    type_of_canvas: ScalarCanvas,
+    bit_size_of_field: 256,
+    modulo_value: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"
+);
+
+impl EllipticCurve for FieldElement {
+    fn coeff_a() -> Self {
+        Self::ZERO() - Self::from_literal(3)
+    }
+    fn coeff_b() -> Self {
+        Self::from_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b")
+    }
+    fn sqrt_exp() -> Self {
+        // (p + 1) / 4, valid since p ≡ 3 (mod 4).
+        Self::from_hex("3fffffffc0000000400000000000000000000000400000000000000000000000")
+    }
+}
+
+impl HashToCurve for FieldElement {
+    fn z() -> Self {
+        // Z = -10, per the RFC 9380 P256_XMD:SHA-256_SSWU_RO_ suite.
+        Self::ZERO() - Self::from_literal(10)
+    }
+    fn l() -> usize {
+        48
+    }
+    fn expand_message_hash(input: &ByteSeq) -> ByteSeq {
+        ByteSeq::from_seq(&sha256(input))
+    }
+    fn hash_output_bytes() -> usize {
+        32
+    }
+    fn hash_block_bytes() -> usize {
+        64
+    }
+}
+
+/// The base point `G`, used as the ECDSA signing generator.
+pub fn generator() -> Affine<FieldElement> {
+    Affine(
+        FieldElement::from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+        FieldElement::from_hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+    )
+}
@@ -0,0 +1,61 @@
+//! NIST P-384 (secp384r1) field and scalar arithmetic.
+
+use hacspec::prelude::*;
+use hacspec_sha384::sha384;
+
+use crate::ec::arithmetic::{Affine, EllipticCurve};
+use crate::ec::hash_to_curve::HashToCurve;
+
+nat_mod!(
+    type_name: FieldElement,
+    type_of_canvas: FieldCanvas,
+    bit_size_of_field: 384,
+    modulo_value: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff"
+);
+
+nat_mod!(
+    type_name: Scalar,
+    type_of_canvas: ScalarCanvas,
+    bit_size_of_field: 384,
+    modulo_value: "00ffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973"
+);
+
+impl EllipticCurve for FieldElement {
+    fn coeff_a() -> Self {
+        Self::ZERO() - Self::from_literal(3)
+    }
+    fn coeff_b() -> Self {
+        Self::from_hex("b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef")
+    }
+    fn sqrt_exp() -> Self {
+        // (p + 1) / 4, valid since p ≡ 3 (mod 4).
+        Self::from_hex("3fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffbfffffffc00000000000000040000000")
+    }
+}
+
+impl HashToCurve for FieldElement {
+    fn z() -> Self {
+        // Z = -12, per the RFC 9380 P384_XMD:SHA-384_SSWU_RO_ suite.
+        Self::ZERO() - Self::from_literal(12)
+    }
+    fn l() -> usize {
+        72
+    }
+    fn expand_message_hash(input: &ByteSeq) -> ByteSeq {
+        ByteSeq::from_seq(&sha384(input))
+    }
+    fn hash_output_bytes() -> usize {
+        48
+    }
+    fn hash_block_bytes() -> usize {
+        128
+    }
+}
+
+/// The base point `G`, used as the ECDSA signing generator.
+pub fn generator() -> Affine<FieldElement> {
+    Affine(
+        FieldElement::from_hex("aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7"),
+        FieldElement::from_hex("3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f"),
+    )
+}
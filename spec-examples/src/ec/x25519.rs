@@ -0,0 +1,87 @@
+//! X25519 (Curve25519) scalar multiplication, per RFC 7748 §5.
+//!
+//! Unlike [`crate::ec::arithmetic`] this only ever needs the u-coordinate,
+//! so it works directly over the Montgomery ladder rather than going
+//! through [`crate::ec::arithmetic::Affine`].
+
+use hacspec::prelude::*;
+
+bytes!(Scalar25519, 32);
+bytes!(FieldCoordinate25519, 32);
+
+nat_mod!(
+    type_name: FieldElement,
+    type_of_canvas: FieldCanvas,
+    bit_size_of_field: 255,
+    modulo_value: "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"
+);
+
+const A24: u32 = 121665;
+
+fn decode_scalar(k: &Scalar25519) -> FieldElement {
+    let mut k = k.clone();
+    k[0] = k[0] & 0xf8u8;
+    k[31] = (k[31] & 0x7fu8) | 0x40u8;
+    FieldElement::from_byte_seq_le(&k)
+}
+
+fn decode_u_coordinate(u: &FieldCoordinate25519) -> FieldElement {
+    let mut u = u.clone();
+    u[31] = u[31] & 0x7fu8; // mask the top bit, per RFC 7748 §5 for interop
+    FieldElement::from_byte_seq_le(&u)
+}
+
+fn cswap(swap: bool, a: FieldElement, b: FieldElement) -> (FieldElement, FieldElement) {
+    if swap {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+/// The Montgomery ladder from RFC 7748 §5. `cswap` here branches on the
+/// swap bit rather than masking, so the ladder is not constant-time.
+pub fn x25519(scalar: Scalar25519, u: FieldCoordinate25519) -> FieldCoordinate25519 {
+    let k = decode_scalar(&scalar);
+    let x1 = decode_u_coordinate(&u);
+    let a24 = FieldElement::from_literal(A24 as u128);
+
+    let (mut x2, mut z2) = (FieldElement::ONE(), FieldElement::ZERO());
+    let (mut x3, mut z3) = (x1, FieldElement::ONE());
+    let mut swap = false;
+
+    for t in 0..255 {
+        let i = 254 - t;
+        let k_t = k.bit(i);
+        swap = swap ^ k_t;
+        let (sx2, sx3) = cswap(swap, x2, x3);
+        let (sz2, sz3) = cswap(swap, z2, z3);
+        x2 = sx2;
+        x3 = sx3;
+        z2 = sz2;
+        z3 = sz3;
+        swap = k_t;
+
+        let a = x2 + z2;
+        let aa = a * a;
+        let b = x2 - z2;
+        let bb = b * b;
+        let e = aa - bb;
+        let c = x3 + z3;
+        let d = x3 - z3;
+        let da = d * a;
+        let cb = c * b;
+        x3 = (da + cb) * (da + cb);
+        z3 = x1 * ((da - cb) * (da - cb));
+        x2 = aa * bb;
+        z2 = e * (aa + a24 * e);
+    }
+    let (fx2, fx3) = cswap(swap, x2, x3);
+    let (fz2, _fz3) = cswap(swap, z2, z3);
+
+    let p_minus_two = FieldElement::from_hex(
+        "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeb",
+    );
+    let result = fx2 * fz2.pow_self(p_minus_two);
+    FieldCoordinate25519::from_seq(&result.to_byte_seq_le())
+}
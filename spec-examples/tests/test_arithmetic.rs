@@ -0,0 +1,32 @@
+//! Regression tests for [`arithmetic::point_mul`] that don't depend on an
+//! external Wycheproof vector file (unlike the other harnesses in this
+//! directory) -- just internal consistency against [`arithmetic::point_add`]/
+//! [`arithmetic::point_double`], which `point_mul` is built from.
+
+use hacspec_examples::ec::{arithmetic, p256};
+
+use hacspec::prelude::*;
+
+/// `k = 1` is the simplest odd scalar: `1 * G` must be `G` itself.
+#[test]
+fn point_mul_by_one_is_identity() {
+    let g = p256::generator();
+    let result = arithmetic::point_mul(p256::Scalar::ONE(), g);
+    assert!(result.0.equal(g.0));
+    assert!(result.1.equal(g.1));
+}
+
+/// `k = 3` (binary `11`) is odd and, unlike `k = 1`, has its final set bit
+/// below the loop's top iteration -- exactly the case a loop bound that
+/// skips the scalar's LSB drops. Checked against `2G + G` computed
+/// directly from `point_double`/`point_add` rather than `point_mul`, so
+/// this doesn't just test `point_mul` against itself.
+#[test]
+fn point_mul_by_three_matches_double_and_add() {
+    let g = p256::generator();
+    let expected = arithmetic::point_add(arithmetic::point_double(g), g);
+    let three = p256::Scalar::ONE() + p256::Scalar::ONE() + p256::Scalar::ONE();
+    let result = arithmetic::point_mul(three, g);
+    assert!(result.0.equal(expected.0));
+    assert!(result.1.equal(expected.1));
+}
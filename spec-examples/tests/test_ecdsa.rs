@@ -0,0 +1,217 @@
+//! Wycheproof `EcdsaVerifyTest` harness for the NIST curves.
+//!
+//! This mirrors [`test_nist_curves`](test_nist_curves.rs): every `tcId`
+//! becomes its own named `Trial` built on `libtest-mimic`, so a single
+//! vector can be targeted with `cargo test --test test_ecdsa --
+//! <filter>`, and known-unsupported vectors are declared in
+//! `tests/wycheproof_ignore.toml` instead of being skipped in code.
+
+use hacspec_examples::ec::{arithmetic::EllipticCurve, ecdsa, hash_to_curve::HashToCurve, p256, p384, Affine};
+
+use hacspec::prelude::*;
+use hacspec_dev::prelude::*;
+
+use libtest_mimic::{Arguments, Trial};
+use std::path::{Path, PathBuf};
+
+create_test_vectors!(
+    TestVector,
+    algorithm: String,
+    generatorVersion: String,
+    numberOfTests: usize,
+    header: Vec<Value>,   // not used
+    notes: Option<Value>, // text notes (might not be present), keys correspond to flags
+    schema: String,       // not used
+    testGroups: Vec<TestGroup>
+);
+
+create_test_vectors!(
+    TestGroup,
+    key: TestKey,
+    r#type: String,
+    tests: Vec<Test>
+);
+
+create_test_vectors!(
+    TestKey,
+    curve: String,
+    wx: String,
+    wy: String
+);
+
+create_test_vectors!(
+    Test,
+    tcId: usize,
+    comment: String,
+    msg: String,
+    sig: String,
+    result: String,
+    flags: Vec<String>
+);
+
+/// A minimal DER parser for the `SEQUENCE { INTEGER r, INTEGER s }`
+/// ECDSA signature encoding -- just enough to pull out `r`/`s` as hex and
+/// flag integers that are too wide to fit the curve's scalar field
+/// (Wycheproof's way of encoding `r, s >= n`).
+fn parse_der_signature(sig: &str, byte_len: usize) -> Option<(String, String)> {
+    let bytes = hex_string_to_bytes(sig);
+    if bytes.len() < 2 || bytes[0] != 0x30 {
+        return None;
+    }
+    let mut pos = 2;
+    let mut ints = Vec::new();
+    for _ in 0..2 {
+        if pos + 2 > bytes.len() || bytes[pos] != 0x02 {
+            return None;
+        }
+        let len = bytes[pos + 1] as usize;
+        pos += 2;
+        if pos + len > bytes.len() {
+            return None;
+        }
+        let mut int_bytes = &bytes[pos..pos + len];
+        while int_bytes.len() > 1 && int_bytes[0] == 0x00 {
+            int_bytes = &int_bytes[1..];
+        }
+        if int_bytes.len() > byte_len {
+            // Too wide to be in [0, n): reject outright.
+            return None;
+        }
+        ints.push(hex::encode(int_bytes));
+        pos += len;
+    }
+    Some((ints[0].clone(), ints[1].clone()))
+}
+
+fn hex_string_to_bytes(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap_or_default()
+}
+
+/// Run a single Wycheproof `tcId` to completion, returning `Err` (rather
+/// than panicking) on failure so `libtest_mimic` can report it per-trial.
+#[allow(non_snake_case)]
+fn run_test_case<Scalar: UnsignedIntegerCopy, FieldElement: EllipticCurve + HashToCurve>(
+    test: &Test,
+    wx: &str,
+    wy: &str,
+    g: Affine<FieldElement>,
+    byte_len: usize,
+) -> Result<(), String> {
+    let q = Affine(
+        FieldElement::from_hex_string(wx),
+        FieldElement::from_hex_string(wy),
+    );
+    let digest = FieldElement::expand_message_hash(&ByteSeq::from_public_slice(
+        &hex_string_to_bytes(&test.msg),
+    ));
+    let msg_hash = Scalar::from_byte_seq_be(&digest);
+    let parsed = parse_der_signature(&test.sig, byte_len);
+
+    match test.result.as_ref() {
+        "valid" | "acceptable" => {
+            let (r_hex, s_hex) = parsed
+                .ok_or_else(|| format!("tcId {} should have a well-formed DER signature", test.tcId))?;
+            let signature = (
+                Scalar::from_hex_string(&r_hex),
+                Scalar::from_hex_string(&s_hex),
+            );
+            let verified = ecdsa::ecdsa_verify(g, q, msg_hash, signature);
+            if !verified {
+                return Err(format!("tcId {} should verify", test.tcId));
+            }
+        }
+        "invalid" => {
+            if let Some((r_hex, s_hex)) = parsed {
+                let signature = (
+                    Scalar::from_hex_string(&r_hex),
+                    Scalar::from_hex_string(&s_hex),
+                );
+                if ecdsa::ecdsa_verify(g, q, msg_hash, signature) {
+                    return Err(format!("tcId {} is invalid but verified", test.tcId));
+                }
+            }
+            // A signature that doesn't even parse as DER is correctly rejected.
+        }
+        other => return Err(format!("Unknown result kind {:?} for tcId {}", other, test.tcId)),
+    }
+    Ok(())
+}
+
+/// Build one `Trial` per `tcId` in `path`, honoring `ignore_list`.
+#[allow(non_snake_case)]
+fn trials_for_file<Scalar, FieldElement>(
+    path: &Path,
+    curve: &'static str,
+    g: Affine<FieldElement>,
+    byte_len: usize,
+    ignore_list: &WycheproofIgnoreList,
+) -> Vec<Trial>
+where
+    Scalar: UnsignedIntegerCopy + Send + Sync + 'static,
+    FieldElement: EllipticCurve + HashToCurve + Send + Sync + 'static,
+{
+    let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+    let tests: TestVector = TestVector::from_file(path.to_str().unwrap());
+    assert_eq!(tests.algorithm, "ECDSA");
+    let mut trials = Vec::with_capacity(tests.numberOfTests);
+    for test_group in tests.testGroups {
+        assert_eq!(test_group.r#type, "EcdsaVerify");
+        assert_eq!(test_group.key.curve, curve);
+        let wx = test_group.key.wx;
+        let wy = test_group.key.wy;
+        for test in test_group.tests {
+            let name = format!("{}::tc{}", file_name, test.tcId);
+            let ignored = ignore_list.reason_for(&file_name, test.tcId, &test.flags);
+            let (wx, wy) = (wx.clone(), wy.clone());
+            let mut trial = Trial::test(name, move || {
+                run_test_case::<Scalar, FieldElement>(&test, &wx, &wy, g, byte_len)
+                    .map_err(|e| e.into())
+            });
+            if let Some(reason) = ignored {
+                trial = trial.with_ignored_flag(true).with_kind(reason);
+            }
+            trials.push(trial);
+        }
+    }
+    trials
+}
+
+/// Every `.json` file under `tests/`, sorted for stable run order; `main`
+/// keeps only the `ecdsa_secp256r1*`/`ecdsa_secp384r1*` ones.
+fn discover_test_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("tests")
+        .expect("tests/ directory should exist")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn main() {
+    let args = Arguments::from_args();
+    let ignore_list = WycheproofIgnoreList::from_file("tests/wycheproof_ignore.toml");
+    let mut trials = Vec::new();
+    for path in discover_test_files() {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        if file_name.starts_with("ecdsa_secp256r1") {
+            trials.extend(trials_for_file::<p256::Scalar, p256::FieldElement>(
+                &path,
+                "secp256r1",
+                p256::generator(),
+                32,
+                &ignore_list,
+            ));
+        } else if file_name.starts_with("ecdsa_secp384r1") {
+            trials.extend(trials_for_file::<p384::Scalar, p384::FieldElement>(
+                &path,
+                "secp384r1",
+                p384::generator(),
+                48,
+                &ignore_list,
+            ));
+        }
+    }
+    libtest_mimic::run(&args, trials).exit();
+}
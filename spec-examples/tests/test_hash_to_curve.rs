@@ -0,0 +1,103 @@
+//! RFC 9380 appendix J test-vector harness for hash-to-curve.
+//!
+//! This mirrors [`test_nist_curves`](test_nist_curves.rs): every vector
+//! becomes its own named `Trial` built on `libtest-mimic`, so a single
+//! vector can be targeted with `cargo test --test test_hash_to_curve --
+//! <filter>` and known-unsupported vectors are declared in
+//! `tests/wycheproof_ignore.toml` instead of being skipped in code.
+
+use hacspec_examples::ec::hash_to_curve::{self, HashToCurve};
+use hacspec_examples::ec::{p256, p384};
+
+use hacspec::prelude::*;
+use hacspec_dev::prelude::*;
+
+use libtest_mimic::{Arguments, Trial};
+use std::path::{Path, PathBuf};
+
+create_test_vectors!(
+    TestVector,
+    L: usize,
+    Z: String,
+    ciphersuite: String,
+    curve: String,
+    dst: String,
+    vectors: Vec<Test>
+);
+
+create_test_vectors!(
+    Test,
+    msg: String,
+    Px: String,
+    Py: String
+);
+
+/// Run a single RFC 9380 vector to completion, returning `Err` (rather
+/// than panicking) on failure so `libtest_mimic` can report it per-trial.
+#[allow(non_snake_case)]
+fn run_test_case<FieldElement: HashToCurve>(test: &Test, dst: &str) -> Result<(), String> {
+    let msg = ByteSeq::from_public_slice(test.msg.as_bytes());
+    let dst = ByteSeq::from_public_slice(dst.as_bytes());
+    let p = hash_to_curve::hash_to_curve::<FieldElement>(&msg, &dst);
+    let expected_x = FieldElement::from_hex_string(&test.Px);
+    let expected_y = FieldElement::from_hex_string(&test.Py);
+    if !p.0.equal(expected_x) || !p.1.equal(expected_y) {
+        return Err(format!("{:?} mapped to an unexpected point", test.msg));
+    }
+    Ok(())
+}
+
+/// Build one `Trial` per vector in `path`, honoring `ignore_list`.
+#[allow(non_snake_case)]
+fn trials_for_file<FieldElement>(path: &Path, ignore_list: &WycheproofIgnoreList) -> Vec<Trial>
+where
+    FieldElement: HashToCurve + Send + Sync + 'static,
+{
+    let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+    let vectors: TestVector = TestVector::from_file(path.to_str().unwrap());
+    let mut trials = Vec::with_capacity(vectors.vectors.len());
+    for (tc_id, test) in vectors.vectors.into_iter().enumerate() {
+        let name = format!("{}::tc{}", file_name, tc_id);
+        let ignored = ignore_list.reason_for(&file_name, tc_id, &[]);
+        let dst = vectors.dst.clone();
+        let mut trial =
+            Trial::test(name, move || run_test_case::<FieldElement>(&test, &dst).map_err(|e| e.into()));
+        if let Some(reason) = ignored {
+            trial = trial.with_ignored_flag(true).with_kind(reason);
+        }
+        trials.push(trial);
+    }
+    trials
+}
+
+/// Every `hash_to_curve_*_test.json` file under `tests/`, sorted for
+/// stable run order.
+fn discover_test_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("tests")
+        .expect("tests/ directory should exist")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| n.starts_with("hash_to_curve_") && n.ends_with("_test.json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn main() {
+    let args = Arguments::from_args();
+    let ignore_list = WycheproofIgnoreList::from_file("tests/wycheproof_ignore.toml");
+    let mut trials = Vec::new();
+    for path in discover_test_files() {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        if file_name.starts_with("hash_to_curve_p256") {
+            trials.extend(trials_for_file::<p256::FieldElement>(&path, &ignore_list));
+        } else if file_name.starts_with("hash_to_curve_p384") {
+            trials.extend(trials_for_file::<p384::FieldElement>(&path, &ignore_list));
+        }
+    }
+    libtest_mimic::run(&args, trials).exit();
+}
@@ -1,8 +1,20 @@
-use hacspec_examples::ec::{arithmetic, p256, p384, Affine};
+//! Wycheproof ECDH harness for the NIST curves.
+//!
+//! This is a custom test harness (requires `harness = false` for this
+//! target) built on `libtest-mimic`: every `tcId` in every
+//! `tests/*_ecpoint_test.json` file becomes its own named `Trial`, so
+//! `cargo test --test test_nist_curves -- <filter>` can target a single
+//! vector, and known-unsupported vectors are declared in
+//! `tests/wycheproof_ignore.toml` instead of being skipped in code.
+
+use hacspec_examples::ec::{arithmetic, arithmetic::EllipticCurve, p256, p384, Affine};
 
 use hacspec::prelude::*;
 use hacspec_dev::prelude::*;
 
+use libtest_mimic::{Arguments, Trial};
+use std::path::{Path, PathBuf};
+
 create_test_vectors!(
     TestVector,
     algorithm: String,
@@ -33,72 +45,165 @@ create_test_vectors!(
     flags: Vec<String>
 );
 
+/// Decode a SEC1 public-key encoding (`04||X||Y` or `02`/`03||X`) into an
+/// affine point, returning `None` for anything that doesn't parse as one of
+/// those two forms.
+fn decode_point<FieldElement: EllipticCurve>(
+    public: &str,
+    point_len: usize,
+) -> Option<Affine<FieldElement>> {
+    if public.len() < 2 {
+        return None;
+    }
+    match &public[0..2] {
+        "04" if public.len() == 2 * point_len + 2 => Some(Affine(
+            FieldElement::from_hex_string(&public[2..point_len + 2].to_string()),
+            FieldElement::from_hex_string(&public[point_len + 2..].to_string()),
+        )),
+        prefix @ ("02" | "03") if public.len() == point_len + 2 => {
+            let x = FieldElement::from_hex_string(&public[2..point_len + 2].to_string());
+            arithmetic::decompress_point(prefix == "03", x)
+        }
+        _ => None,
+    }
+}
+
+/// Run a single Wycheproof `tcId` to completion, returning `Err` (rather
+/// than panicking) on failure so `libtest_mimic` can report it per-trial.
 #[allow(non_snake_case)]
-fn run_test<Scalar: UnsignedIntegerCopy, FieldElement: UnsignedIntegerCopy>(
-    tests: TestVector,
-    curve: &'static str,
-) {
-    let num_tests = tests.numberOfTests;
-    let mut skipped_tests = 0;
-    let mut tests_run = 0;
-    match tests.algorithm.as_ref() {
-        "ECDH" => (),
-        _ => panic!("This is not an ECDH test vector."),
-    };
-    for testGroup in tests.testGroups.iter() {
-        assert_eq!(testGroup.r#type, "EcdhEcpointTest");
-        assert_eq!(testGroup.curve, curve);
-        assert_eq!(testGroup.encoding, "ecpoint");
-        let point_len = match curve {
-            "secp256r1" => 64,
-            "secp384r1" => 96,
-            _ => panic!("I don't know that curve"),
-        };
-        for test in testGroup.tests.iter() {
-            println!("Test {:?}: {:?}", test.tcId, test.comment);
-            if !test.result.eq("valid") {
-                println!("We're only doing valid tests for now.");
-                skipped_tests += 1;
-                continue;
-            }
-            if test.comment == "compressed public key" {
-                // not implemented
-                println!("Compressed public keys are not supported.");
-                skipped_tests += 1;
-                continue;
+fn run_test_case<Scalar: UnsignedIntegerCopy, FieldElement: EllipticCurve>(
+    test: &Test,
+    point_len: usize,
+) -> Result<(), String> {
+    let k = Scalar::from_hex_string(&test.private);
+    let decoded = decode_point::<FieldElement>(&test.public, point_len);
+    match test.result.as_ref() {
+        "valid" => {
+            let p = decoded.ok_or_else(|| format!("tcId {} should decode", test.tcId))?;
+            if !arithmetic::validate_point(p) {
+                return Err(format!("tcId {} should be on-curve", test.tcId));
             }
-            assert_eq!(&test.public[0..2], "04");
-            let k = Scalar::from_hex_string(&test.private);
-            // println!("k: {:?}", k);
-            let p = Affine(
-                FieldElement::from_hex_string(&test.public[2..point_len + 2].to_string()),
-                FieldElement::from_hex_string(&test.public[point_len + 2..].to_string()),
-            );
-            // println!("p: {:?}", p);
             let expected = FieldElement::from_hex_string(&test.shared);
-            // println!("expected: {:?}", expected);
             let shared = arithmetic::point_mul(k, p);
-            // println!("computed: {:?}", shared);
-            assert!(shared.0.equal(expected));
-            tests_run += 1;
+            if !shared.0.equal(expected) {
+                return Err(format!("tcId {} produced an unexpected shared value", test.tcId));
+            }
+        }
+        "invalid" => {
+            // The spec must either reject the encoding outright, or (if it
+            // happens to decode) must not derive the shared value the
+            // vector would have produced for a valid peer.
+            if let Some(p) = decoded {
+                if arithmetic::validate_point(p) && !test.shared.is_empty() {
+                    let expected = FieldElement::from_hex_string(&test.shared);
+                    let shared = arithmetic::point_mul(k, p);
+                    if shared.0.equal(expected) {
+                        return Err(format!(
+                            "tcId {} is invalid but produced the expected shared value",
+                            test.tcId
+                        ));
+                    }
+                }
+            }
         }
+        "acceptable" => {
+            // Wycheproof allows implementations to accept or reject these;
+            // known-tolerated encoding quirks (recorded via `flags`) are
+            // exempted from the equality check.
+            if let Some(p) = decoded {
+                if arithmetic::validate_point(p) && !test.shared.is_empty() {
+                    let tolerated = test
+                        .flags
+                        .iter()
+                        .any(|f| f == "CompressedPoint" || f == "InvalidAsn");
+                    if !tolerated {
+                        let expected = FieldElement::from_hex_string(&test.shared);
+                        let shared = arithmetic::point_mul(k, p);
+                        if !shared.0.equal(expected) {
+                            return Err(format!(
+                                "tcId {} is acceptable but did not match the expected shared value",
+                                test.tcId
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        other => return Err(format!("Unknown result kind {:?} for tcId {}", other, test.tcId)),
     }
-    // Check that we ran all tests.
-    println!(
-        "Ran {} out of {} tests and skipped {}.",
-        tests_run, num_tests, skipped_tests
-    );
-    assert_eq!(num_tests - skipped_tests, tests_run);
+    Ok(())
 }
 
-#[test]
-fn test_wycheproof_384_plain() {
-    let tests: TestVector = TestVector::from_file("tests/ecdh_secp384r1_ecpoint_test.json");
-    run_test::<p384::Scalar, p384::FieldElement>(tests, "secp384r1");
+/// Build one `Trial` per `tcId` in `path`, honoring `ignore_list`.
+#[allow(non_snake_case)]
+fn trials_for_file<Scalar, FieldElement>(
+    path: &Path,
+    curve: &'static str,
+    point_len: usize,
+    ignore_list: &WycheproofIgnoreList,
+) -> Vec<Trial>
+where
+    Scalar: UnsignedIntegerCopy + Send + Sync + 'static,
+    FieldElement: EllipticCurve + Send + Sync + 'static,
+{
+    let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+    let tests: TestVector = TestVector::from_file(path.to_str().unwrap());
+    assert_eq!(tests.algorithm, "ECDH");
+    let mut trials = Vec::with_capacity(tests.numberOfTests);
+    for test_group in tests.testGroups {
+        assert_eq!(test_group.r#type, "EcdhEcpointTest");
+        assert_eq!(test_group.curve, curve);
+        assert_eq!(test_group.encoding, "ecpoint");
+        for test in test_group.tests {
+            let name = format!("{}::tc{}", file_name, test.tcId);
+            let ignored = ignore_list.reason_for(&file_name, test.tcId, &test.flags);
+            let mut trial = Trial::test(name, move || {
+                run_test_case::<Scalar, FieldElement>(&test, point_len)
+                    .map_err(|e| e.into())
+            });
+            if let Some(reason) = ignored {
+                trial = trial.with_ignored_flag(true).with_kind(reason);
+            }
+            trials.push(trial);
+        }
+    }
+    trials
+}
+
+/// Every `.json` file under `tests/`, sorted for stable run order; `main`
+/// keeps only the `ecdh_secp256r1*`/`ecdh_secp384r1*` ones.
+fn discover_test_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("tests")
+        .expect("tests/ directory should exist")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
 }
 
-#[test]
-fn test_wycheproof_256_plain() {
-    let tests: TestVector = TestVector::from_file("tests/ecdh_secp256r1_ecpoint_test.json");
-    run_test::<p256::Scalar, p256::FieldElement>(tests, "secp256r1");
+fn main() {
+    let args = Arguments::from_args();
+    let ignore_list = WycheproofIgnoreList::from_file("tests/wycheproof_ignore.toml");
+    let mut trials = Vec::new();
+    for path in discover_test_files() {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        if file_name.starts_with("ecdh_secp256r1") {
+            trials.extend(trials_for_file::<p256::Scalar, p256::FieldElement>(
+                &path,
+                "secp256r1",
+                64,
+                &ignore_list,
+            ));
+        } else if file_name.starts_with("ecdh_secp384r1") {
+            trials.extend(trials_for_file::<p384::Scalar, p384::FieldElement>(
+                &path,
+                "secp384r1",
+                96,
+                &ignore_list,
+            ));
+        }
+    }
+    libtest_mimic::run(&args, trials).exit();
 }
@@ -0,0 +1,127 @@
+//! RFC 7748 / Wycheproof X25519 harness.
+//!
+//! This mirrors [`test_nist_curves`](test_nist_curves.rs): every `tcId`
+//! in a Wycheproof-format `x25519*_test.json` file becomes its own named
+//! `Trial` (the RFC 7748 §5.2 iterated vectors are not run), built on
+//! `libtest-mimic` so a single vector can be
+//! targeted with `cargo test --test test_x25519 -- <filter>` and
+//! known-unsupported vectors are declared in `tests/wycheproof_ignore.toml`
+//! instead of being skipped in code.
+
+use hacspec_examples::ec::x25519::{self, FieldCoordinate25519, Scalar25519};
+
+use hacspec::prelude::*;
+use hacspec_dev::prelude::*;
+
+use libtest_mimic::{Arguments, Trial};
+use std::path::{Path, PathBuf};
+
+create_test_vectors!(
+    TestVector,
+    algorithm: String,
+    generatorVersion: String,
+    numberOfTests: usize,
+    header: Vec<Value>,   // not used
+    notes: Option<Value>, // text notes (might not be present), keys correspond to flags
+    schema: String,       // not used
+    testGroups: Vec<TestGroup>
+);
+
+create_test_vectors!(
+    TestGroup,
+    curve: String,
+    tests: Vec<Test>
+);
+
+create_test_vectors!(
+    Test,
+    tcId: usize,
+    comment: String,
+    public: String,
+    private: String,
+    shared: String,
+    result: String,
+    flags: Vec<String>
+);
+
+/// Run a single Wycheproof `tcId` to completion, returning `Err` (rather
+/// than panicking) on failure so `libtest_mimic` can report it per-trial.
+#[allow(non_snake_case)]
+fn run_test_case(test: &Test) -> Result<(), String> {
+    let k = Scalar25519::from_hex(&test.private);
+    let u = FieldCoordinate25519::from_hex(&test.public);
+    let shared = x25519::x25519(k, u);
+    let matches = shared.equal(FieldCoordinate25519::from_hex(&test.shared));
+    match test.result.as_ref() {
+        "valid" => {
+            if !matches {
+                return Err(format!(
+                    "tcId {} produced an unexpected shared value",
+                    test.tcId
+                ));
+            }
+        }
+        "acceptable" => {
+            // Wycheproof allows implementations to accept or reject these
+            // (e.g. low-order public values); the ladder always produces
+            // *some* output, so only check it against `shared` when given.
+            if !test.shared.is_empty() && !matches {
+                return Err(format!(
+                    "tcId {} is acceptable but did not match the expected shared value",
+                    test.tcId
+                ));
+            }
+        }
+        other => return Err(format!("Unknown result kind {:?} for tcId {}", other, test.tcId)),
+    }
+    Ok(())
+}
+
+/// Build one `Trial` per `tcId` in `path`, honoring `ignore_list`.
+#[allow(non_snake_case)]
+fn trials_for_file(path: &Path, ignore_list: &WycheproofIgnoreList) -> Vec<Trial> {
+    let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+    let tests: TestVector = TestVector::from_file(path.to_str().unwrap());
+    assert_eq!(tests.algorithm, "X25519");
+    let mut trials = Vec::with_capacity(tests.numberOfTests);
+    for test_group in tests.testGroups {
+        assert_eq!(test_group.curve, "curve25519");
+        for test in test_group.tests {
+            let name = format!("{}::tc{}", file_name, test.tcId);
+            let ignored = ignore_list.reason_for(&file_name, test.tcId, &test.flags);
+            let mut trial =
+                Trial::test(name, move || run_test_case(&test).map_err(|e| e.into()));
+            if let Some(reason) = ignored {
+                trial = trial.with_ignored_flag(true).with_kind(reason);
+            }
+            trials.push(trial);
+        }
+    }
+    trials
+}
+
+/// Every `x25519_test.json` file under `tests/`, sorted for stable run order.
+fn discover_test_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("tests")
+        .expect("tests/ directory should exist")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| n.starts_with("x25519") && n.ends_with("_test.json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn main() {
+    let args = Arguments::from_args();
+    let ignore_list = WycheproofIgnoreList::from_file("tests/wycheproof_ignore.toml");
+    let mut trials = Vec::new();
+    for path in discover_test_files() {
+        trials.extend(trials_for_file(&path, &ignore_list));
+    }
+    libtest_mimic::run(&args, trials).exit();
+}